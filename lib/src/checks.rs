@@ -0,0 +1,168 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::{self, Display};
+use std::path::Path;
+
+use uuid::Uuid;
+
+use crate::{
+    diagnostics::{Diagnostics, QuestionLocation, Rule},
+    helpers::normalize_text,
+    CourseData,
+};
+
+#[derive(Clone, Copy, Debug)]
+enum IdLocation<'a> {
+    Question {
+        file: &'a Path,
+        question: &'a QuestionLocation,
+    },
+    QuestionOption {
+        file: &'a Path,
+        question: &'a QuestionLocation,
+    },
+}
+
+impl<'a> IdLocation<'a> {
+    fn file(&self) -> &'a Path {
+        match self {
+            Self::Question { file, .. } | Self::QuestionOption { file, .. } => file,
+        }
+    }
+
+    fn question(&self) -> &'a QuestionLocation {
+        match self {
+            Self::Question { question, .. } | Self::QuestionOption { question, .. } => question,
+        }
+    }
+}
+
+impl Display for IdLocation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Question { file, question } => {
+                write!(f, "{question} in {}", file.display())
+            }
+            Self::QuestionOption { file, question } => {
+                write!(f, "an option of {question} in {}", file.display())
+            }
+        }
+    }
+}
+
+pub fn check_courses(courses_data: &[CourseData], diagnostics: &mut Diagnostics) {
+    check_unique_ids(courses_data, diagnostics);
+    check_course_names(courses_data, diagnostics);
+}
+
+fn check_course_names(courses_data: &[CourseData], diagnostics: &mut Diagnostics) {
+    let mut names: BTreeMap<String, Vec<(&CourseData, &str, &str)>> = BTreeMap::new();
+
+    for course_data in courses_data {
+        let mut course_names = vec![
+            ("key", course_data.key.as_str()),
+            ("short name", course_data.short_name.as_str()),
+        ];
+        course_names.extend(
+            course_data
+                .aliases
+                .iter()
+                .map(|alias| ("alias", alias.as_str())),
+        );
+
+        let mut seen_names = HashSet::new();
+
+        for (kind, name) in course_names {
+            let normalized_name = normalize_text(name);
+
+            if seen_names.insert(normalized_name.clone()) {
+                names
+                    .entry(normalized_name)
+                    .or_default()
+                    .push((course_data, kind, name));
+            }
+        }
+    }
+
+    for entries in names.values().filter(|entries| entries.len() > 1) {
+        for (index, (course_data, kind, name)) in entries.iter().enumerate() {
+            let others = entries
+                .iter()
+                .enumerate()
+                .filter(|(other_index, _)| *other_index != index)
+                .map(|(_, (other_course, other_kind, other_name))| {
+                    format!(
+                        "{other_kind} `{other_name}` of course `{}` in {}",
+                        other_course.key,
+                        other_course.path.display()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            diagnostics.push(
+                &course_data.path,
+                None,
+                Rule::CourseNameCollision,
+                format!("{kind} `{name}` collides with {others}"),
+            );
+        }
+    }
+}
+
+fn check_unique_ids(courses_data: &[CourseData], diagnostics: &mut Diagnostics) {
+    let mut locations: HashMap<Uuid, Vec<IdLocation>> = HashMap::new();
+
+    for course_data in courses_data {
+        let file = course_data.path.as_path();
+
+        for question in &course_data.questions {
+            locations
+                .entry(question.id)
+                .or_default()
+                .push(IdLocation::Question {
+                    file,
+                    question: &question.location,
+                });
+
+            for question_option in &question.question_options {
+                locations
+                    .entry(question_option.id)
+                    .or_default()
+                    .push(IdLocation::QuestionOption {
+                        file,
+                        question: &question.location,
+                    });
+            }
+        }
+    }
+
+    let mut duplicates: Vec<(Uuid, Vec<IdLocation>)> = locations
+        .into_iter()
+        .filter(|(_, locations)| locations.len() > 1)
+        .collect();
+    duplicates.sort_by_key(|(id, _)| *id);
+
+    for (id, locations) in duplicates {
+        for (index, location) in locations.iter().enumerate() {
+            let others = locations
+                .iter()
+                .enumerate()
+                .filter(|(other_index, _)| *other_index != index)
+                .map(|(_, other)| other.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            let subject = match location {
+                IdLocation::Question { .. } => "question id".to_owned(),
+                IdLocation::QuestionOption { .. } => format!("option id {id}"),
+            };
+
+            diagnostics.push(
+                location.file(),
+                Some(location.question()),
+                Rule::DuplicateId,
+                format!("{subject} is also used by {others}"),
+            );
+        }
+    }
+}
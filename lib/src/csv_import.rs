@@ -0,0 +1,124 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDate;
+use csv::ReaderBuilder;
+use serde::Deserialize;
+
+use crate::{QuestionKind, QuestionSource, RawQuestionData, RawQuestionOptionData};
+
+const OPTION_LETTERS: [char; 5] = ['A', 'B', 'C', 'D', 'E'];
+
+#[derive(Deserialize, Debug)]
+struct CsvQuestionRecord {
+    evaluation: String,
+    source: QuestionSource,
+    date: Option<NaiveDate>,
+    text: String,
+    #[serde(alias = "option_a")]
+    a: Option<String>,
+    #[serde(alias = "option_b")]
+    b: Option<String>,
+    #[serde(alias = "option_c")]
+    c: Option<String>,
+    #[serde(alias = "option_d")]
+    d: Option<String>,
+    #[serde(alias = "option_e")]
+    e: Option<String>,
+    correct: String,
+    explanation: Option<String>,
+    image: Option<PathBuf>,
+}
+
+impl CsvQuestionRecord {
+    fn into_raw(self) -> Result<RawQuestionData> {
+        let correct_letters: Vec<char> = self
+            .correct
+            .chars()
+            .filter(|character| character.is_alphabetic())
+            .map(|character| character.to_ascii_uppercase())
+            .collect();
+
+        if correct_letters.is_empty() {
+            bail!("no correct option");
+        }
+
+        if let Some(letter) = correct_letters
+            .iter()
+            .find(|letter| !OPTION_LETTERS.contains(letter))
+        {
+            bail!("unknown correct option `{letter}`");
+        }
+
+        let mut options = vec![];
+
+        for (letter, text) in OPTION_LETTERS
+            .into_iter()
+            .zip([self.a, self.b, self.c, self.d, self.e])
+        {
+            let correct = correct_letters.contains(&letter);
+
+            let text = match text {
+                Some(text) if !text.trim().is_empty() => text,
+                _ if correct => bail!("correct option `{letter}` is empty"),
+                _ => continue,
+            };
+
+            options.push(RawQuestionOptionData {
+                id: None,
+                text,
+                correct: if correct { Some(true) } else { None },
+                explanation: if correct {
+                    self.explanation.clone()
+                } else {
+                    None
+                },
+            });
+        }
+
+        Ok(RawQuestionData {
+            id: None,
+            evaluation: self.evaluation,
+            source: self.source,
+            asked_at: self.date,
+            occurrences: vec![],
+            kind: if correct_letters.len() > 1 {
+                QuestionKind::MultipleChoice
+            } else {
+                QuestionKind::SingleChoice
+            },
+            text: self.text,
+            image: self.image,
+            options,
+        })
+    }
+}
+
+pub fn read_csv_questions(path: &Path) -> Result<Vec<RawQuestionData>> {
+    let delimiter = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("tsv") => b'\t',
+        _ => b',',
+    };
+
+    let mut reader = ReaderBuilder::new()
+        .delimiter(delimiter)
+        .trim(csv::Trim::All)
+        .from_path(path)
+        .with_context(|| format!("Could not read {}", path.display()))?;
+
+    let mut questions = vec![];
+
+    for (index, record) in reader.deserialize::<CsvQuestionRecord>().enumerate() {
+        // Row 1 is the header.
+        let row = index + 2;
+
+        let question = record
+            .map_err(anyhow::Error::from)
+            .and_then(CsvQuestionRecord::into_raw)
+            .with_context(|| format!("Invalid row {row} in {}", path.display()))?;
+
+        questions.push(question);
+    }
+
+    Ok(questions)
+}
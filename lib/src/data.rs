@@ -1,15 +1,23 @@
-use std::fs::DirEntry;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::{self, Display};
+use std::fs::{self, DirEntry};
+use std::io;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::{cmp::Ordering, ffi::OsStr};
 
-use anyhow::{bail, Result};
+use anyhow::{anyhow, Context, Result};
 use chrono::NaiveDate;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
+    diagnostics::{Diagnostics, QuestionLocation, Rule},
     hashable::Hashable,
     helpers::{read_dir_entry_data, write_data},
+    images::{ImageData, ImageFormat},
     RawCourseData,
 };
 use crate::{
@@ -24,29 +32,43 @@ pub struct CourseData {
     pub short_name: String,
     pub aliases: Vec<String>,
     pub year: Option<i16>,
+    #[serde(skip)]
+    pub rules: QuestionRules,
 
     #[serde(skip)]
     pub questions: Vec<QuestionData>,
     pub evaluations: Vec<CourseEvaluationData>,
 
     pub hash: String,
+
+    #[serde(skip)]
+    pub path: PathBuf,
+    #[serde(skip)]
+    pub merged_ids: MergedIds,
 }
 
 impl CourseData {
     pub fn new(key: String, raw: RawCourseData) -> Self {
-        let questions: Vec<QuestionData> = raw.questions.into_iter().map(Into::into).collect();
+        let mut questions: Vec<QuestionData> = raw.questions.into_iter().map(Into::into).collect();
         let evaluations: Vec<CourseEvaluationData> =
             raw.evaluations.into_iter().map(Into::into).collect();
 
+        for (index, question) in questions.iter_mut().enumerate() {
+            question.location.index = index;
+        }
+
         let mut data = Self {
             key,
             name: raw.name,
             short_name: raw.short_name,
             aliases: raw.aliases,
             year: raw.year,
+            rules: raw.rules.unwrap_or_default(),
             questions,
             evaluations,
             hash: Default::default(),
+            path: Default::default(),
+            merged_ids: raw.merged_ids,
         };
 
         data.set_hash();
@@ -54,25 +76,22 @@ impl CourseData {
         data
     }
 
-    pub async fn load_and_write_formatted(
-        dir_entry: DirEntry,
-        mut images_path: PathBuf,
-    ) -> Result<Self> {
-        let path = dir_entry.path();
-        let mut data = Self::load(path.clone(), dir_entry)?;
+    pub async fn write_formatted(&mut self, mut images_path: PathBuf) -> Result<()> {
+        images_path.push(self.key.clone());
+        self.format();
+        self.rename_images(images_path).await?;
 
-        data.check()?;
-        data.deduplicate();
-        data.sort();
-
-        data.set_data();
+        self.clone().write(self.path.clone())
+    }
 
-        images_path.push(data.key.clone());
-        data.format(images_path).await?;
+    pub fn prepare(&mut self, known_ids: &HashSet<Uuid>) -> MergedIds {
+        let merged_ids = self.deduplicate(known_ids);
+        self.sort();
 
-        data.clone().write(path)?;
+        self.set_data();
+        self.set_hash();
 
-        Ok(data)
+        merged_ids
     }
 
     pub fn load(path: PathBuf, dir_entry: DirEntry) -> Result<Self> {
@@ -85,7 +104,10 @@ impl CourseData {
             .to_owned();
         let raw_course_data = RawCourseData::from_slice(&raw_data[..])?;
 
-        Ok(Self::new(key, raw_course_data))
+        let mut data = Self::new(key, raw_course_data);
+        data.path = path;
+
+        Ok(data)
     }
 
     pub fn write(self, path: PathBuf) -> Result<()> {
@@ -113,20 +135,125 @@ impl CourseData {
         }
     }
 
-    fn deduplicate(&mut self) {
-        self.questions.dedup_by(|a, b| a.eq_data(b));
+    fn deduplicate(&mut self, known_ids: &HashSet<Uuid>) -> MergedIds {
+        let mut merged_ids = MergedIds::default();
+        let mut questions = mem::take(&mut self.questions);
+        questions.sort_by_key(|question| (!known_ids.contains(&question.id), question.id));
 
-        for question in self.questions.iter_mut() {
-            question.deduplicate_options();
+        for mut question in questions {
+            question.deduplicate_options(known_ids, &mut merged_ids);
+
+            match self
+                .questions
+                .iter_mut()
+                .find(|kept_question| kept_question.eq_data(&question))
+            {
+                Some(kept_question) => kept_question.merge(question, &mut merged_ids),
+                None => self.questions.push(question),
+            }
         }
+
+        self.merged_ids.extend(&merged_ids);
+
+        merged_ids
     }
 
-    fn check(&self) -> Result<()> {
+    pub fn check(&self, images_path: &Path, diagnostics: &mut Diagnostics) {
+        let mut images_path = images_path.to_owned();
+        images_path.push(&self.key);
+
+        let mut unused_evaluations: BTreeSet<&str> = self
+            .evaluations
+            .iter()
+            .map(|evaluation| evaluation.key.as_str())
+            .collect();
+
+        self.check_rules(diagnostics);
+
         for question in &self.questions {
-            question.check()?;
+            question.check(&self.rules, &self.path, diagnostics);
+            question.check_image(&images_path, &self.path, diagnostics);
+
+            self.check_evaluation(question, &question.evaluation, question.source, diagnostics);
+            unused_evaluations.remove(question.evaluation.as_str());
+
+            for occurrence in &question.occurrences {
+                self.check_evaluation(
+                    question,
+                    &occurrence.evaluation,
+                    occurrence.source,
+                    diagnostics,
+                );
+                unused_evaluations.remove(occurrence.evaluation.as_str());
+            }
         }
 
-        Ok(())
+        for evaluation_key in unused_evaluations {
+            diagnostics.push(
+                &self.path,
+                None,
+                Rule::UnusedEvaluation,
+                format!("evaluation `{evaluation_key}` is not used by any question"),
+            );
+        }
+    }
+
+    fn check_rules(&self, diagnostics: &mut Diagnostics) {
+        let QuestionRules {
+            min_options,
+            max_options,
+        } = self.rules;
+
+        if min_options == 0 {
+            diagnostics.push(
+                &self.path,
+                None,
+                Rule::InvalidRules,
+                "`min_options` must be at least 1",
+            );
+        }
+
+        if min_options > max_options {
+            diagnostics.push(
+                &self.path,
+                None,
+                Rule::InvalidRules,
+                format!(
+                    "`min_options` ({min_options}) is greater than `max_options` ({max_options})"
+                ),
+            );
+        }
+    }
+
+    fn check_evaluation(
+        &self,
+        question: &QuestionData,
+        evaluation_key: &str,
+        source: QuestionSource,
+        diagnostics: &mut Diagnostics,
+    ) {
+        match self
+            .evaluations
+            .iter()
+            .find(|evaluation| evaluation.key == evaluation_key)
+        {
+            Some(evaluation) if !evaluation.accepts(source) => diagnostics.push(
+                &self.path,
+                Some(&question.location),
+                Rule::SourceNotAccepted,
+                format!(
+                    "source `{source}` is not accepted by evaluation `{}`",
+                    evaluation.key
+                ),
+            ),
+            Some(_) => {}
+            None => diagnostics.push(
+                &self.path,
+                Some(&question.location),
+                Rule::UnknownEvaluation,
+                format!("references unknown evaluation `{evaluation_key}`"),
+            ),
+        }
     }
 
     fn set_data(&mut self) {
@@ -139,9 +266,15 @@ impl CourseData {
         }
     }
 
-    async fn format(&mut self, images_path: PathBuf) -> Result<()> {
+    pub fn format(&mut self) {
         for question in &mut self.questions {
-            question.format(images_path.clone()).await?;
+            question.format();
+        }
+    }
+
+    async fn rename_images(&mut self, images_path: PathBuf) -> Result<()> {
+        for question in &mut self.questions {
+            question.rename_image(images_path.clone()).await?;
         }
 
         Ok(())
@@ -186,62 +319,116 @@ pub struct QuestionData {
 
     pub course_key: Option<String>,
     pub evaluation: String,
-    pub source: String,
+    pub source: QuestionSource,
     pub asked_at: Option<NaiveDate>,
+    #[serde(default)]
+    pub occurrences: Vec<QuestionOccurrenceData>,
+    #[serde(default)]
+    pub kind: QuestionKind,
     pub text: String,
     pub image_file_name: Option<PathBuf>,
     #[serde(skip)]
+    pub unformatted_image_file_name: Option<PathBuf>,
+    #[serde(skip)]
+    pub location: QuestionLocation,
+    #[serde(skip)]
     pub question_options: Vec<QuestionOptionData>,
 
     pub hash: String,
 }
 
 impl QuestionData {
-    fn new(
-        id: Uuid,
-        text: String,
-        image_file_name: Option<PathBuf>,
-        question_options: Vec<QuestionOptionData>,
-        evaluation: String,
-        source: String,
-        asked_at: Option<NaiveDate>,
-    ) -> Self {
-        let mut data = Self {
-            id,
-            course_key: None,
-            evaluation,
-            source,
-            asked_at,
-            text,
-            image_file_name: image_file_name,
-            question_options,
-            hash: Default::default(),
-        };
+    fn sort_options(&mut self) {
+        self.question_options
+            .sort_by(|a, b| b.correct.cmp(&a.correct).then_with(|| a.text.cmp(&b.text)))
+    }
 
-        data.set_hash();
+    fn deduplicate_options(&mut self, known_ids: &HashSet<Uuid>, merged_ids: &mut MergedIds) {
+        let mut question_options = mem::take(&mut self.question_options);
+        question_options.sort_by_key(|question_option| {
+            (!known_ids.contains(&question_option.id), question_option.id)
+        });
 
-        data
+        for question_option in question_options {
+            match self
+                .question_options
+                .iter()
+                .find(|kept_option| kept_option.eq_data(&question_option))
+            {
+                Some(kept_option) => {
+                    merged_ids.insert_question_option(question_option.id, kept_option.id)
+                }
+                None => self.question_options.push(question_option),
+            }
+        }
+
+        self.set_hash();
     }
 
-    fn sort_options(&mut self) {
-        self.question_options.sort_by(|a, b| {
-            if a.correct {
-                Ordering::Less
-            } else if b.correct {
-                Ordering::Greater
-            } else {
-                a.text.cmp(&b.text)
+    fn merge(&mut self, other: Self, merged_ids: &mut MergedIds) {
+        merged_ids.insert_question(other.id, self.id);
+
+        for question_option in &other.question_options {
+            if let Some(kept_option) = self
+                .question_options
+                .iter()
+                .find(|kept_option| kept_option.eq_data(question_option))
+            {
+                merged_ids.insert_question_option(question_option.id, kept_option.id);
             }
-        })
+        }
+
+        self.add_occurrence(other.occurrence());
+
+        if self.image_file_name.is_none() {
+            self.image_file_name = other.image_file_name;
+        }
+
+        for occurrence in other.occurrences {
+            self.add_occurrence(occurrence);
+        }
+
+        self.occurrences.sort();
+
+        self.set_hash();
     }
 
-    fn deduplicate_options(&mut self) {
-        self.question_options.dedup_by(|a, b| a.eq_data(b));
+    fn add_occurrence(&mut self, occurrence: QuestionOccurrenceData) {
+        if self.occurrence().eq_data(&occurrence) {
+            self.asked_at = self.asked_at.or(occurrence.asked_at);
+
+            return;
+        }
+
+        match self
+            .occurrences
+            .iter_mut()
+            .find(|existing| existing.eq_data(&occurrence))
+        {
+            Some(existing) => existing.asked_at = existing.asked_at.or(occurrence.asked_at),
+            None => self.occurrences.push(occurrence),
+        }
+    }
+
+    pub fn occurrence(&self) -> QuestionOccurrenceData {
+        QuestionOccurrenceData {
+            asked_at: self.asked_at,
+            evaluation: self.evaluation.clone(),
+            source: self.source,
+        }
     }
 
     fn eq_data(&self, other: &Self) -> bool {
+        let same_image = match (&self.image_file_name, &other.image_file_name) {
+            (Some(image_file_name), Some(other_image_file_name)) => {
+                image_file_name == other_image_file_name
+            }
+            _ => true,
+        };
+
         self.text == other.text
-            && self.evaluation == other.evaluation
+            && self.kind == other.kind
+            && same_image
             && self.question_options.len() == other.question_options.len()
             && self
                 .question_options
@@ -249,12 +436,18 @@ impl QuestionData {
                 .all(|a| other.question_options.iter().any(|b| a.eq_data(b)))
     }
 
-    fn check(&self) -> Result<()> {
-        if self.question_options.len() < 2 || self.question_options.len() > 5 {
-            bail!(
-                "Question {} has {} option(s)",
-                self.id,
-                self.question_options.len()
+    fn check(&self, rules: &QuestionRules, path: &Path, diagnostics: &mut Diagnostics) {
+        let option_count = self.question_options.len();
+
+        if option_count < rules.min_options || option_count > rules.max_options {
+            diagnostics.push(
+                path,
+                Some(&self.location),
+                Rule::OptionCount,
+                format!(
+                    "has {option_count} option(s), expected between {} and {}",
+                    rules.min_options, rules.max_options
+                ),
             );
         }
 
@@ -264,20 +457,84 @@ impl QuestionData {
             .filter(|option| option.correct)
             .count();
 
-        if correct_count != 1 {
-            bail!("Question {} has {correct_count} correct options", self.id)
+        let valid_correct_count = match self.kind {
+            QuestionKind::SingleChoice => correct_count == 1,
+            QuestionKind::MultipleChoice => correct_count >= 1,
+        };
+
+        if !valid_correct_count {
+            diagnostics.push(
+                path,
+                Some(&self.location),
+                Rule::CorrectOptionCount,
+                format!("{} question has {correct_count} correct options", self.kind),
+            );
         }
+    }
 
-        Ok(())
+    fn check_image(&self, images_path: &Path, path: &Path, diagnostics: &mut Diagnostics) {
+        let image_file_name = match &self.image_file_name {
+            Some(image_file_name) => image_file_name,
+            None => return,
+        };
+
+        let image_format = match ImageFormat::from_path(image_file_name) {
+            Some(image_format) => image_format,
+            None => {
+                diagnostics.push(
+                    path,
+                    Some(&self.location),
+                    Rule::InvalidImage,
+                    format!(
+                        "image `{}` has an unsupported extension",
+                        image_file_name.display()
+                    ),
+                );
+
+                return;
+            }
+        };
+
+        let mut image_path = images_path.to_owned();
+        image_path.push(image_file_name);
+
+        match fs::read(&image_path) {
+            Ok(content) if image_format.matches(&content) => {}
+            Ok(_) => diagnostics.push(
+                path,
+                Some(&self.location),
+                Rule::InvalidImage,
+                format!(
+                    "image `{}` is not a valid {} file",
+                    image_path.display(),
+                    image_format.content_type()
+                ),
+            ),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => diagnostics.push(
+                path,
+                Some(&self.location),
+                Rule::MissingImage,
+                format!("image `{}` does not exist", image_path.display()),
+            ),
+            Err(error) => diagnostics.push(
+                path,
+                Some(&self.location),
+                Rule::InvalidImage,
+                format!(
+                    "image `{}` could not be read: {error}",
+                    image_path.display()
+                ),
+            ),
+        }
     }
 
-    async fn format(&mut self, images_path: PathBuf) -> Result<()> {
+    fn format(&mut self) {
         self.text = self.text.trim().into();
 
         if let Some(image_file_name) = &self.image_file_name {
             let stem = image_file_name.file_stem().and_then(OsStr::to_str).unwrap();
 
-            if stem != &self.id.to_string() {
+            if stem != self.id.to_string() {
                 let extension = image_file_name
                     .extension()
                     .and_then(OsStr::to_str)
@@ -286,19 +543,39 @@ impl QuestionData {
                 let mut new_file_name = PathBuf::from(self.id.to_string());
                 new_file_name.set_extension(extension);
 
-                let mut old_path = images_path.clone();
-                old_path.push(image_file_name);
-                let mut new_path = images_path.clone();
-                new_path.push(new_file_name.clone());
-                tokio::fs::rename(old_path, new_path).await?;
-
-                self.image_file_name.replace(new_file_name);
+                self.unformatted_image_file_name = self.image_file_name.replace(new_file_name);
             }
         }
 
         for question_option in self.question_options.iter_mut() {
             question_option.format();
         }
+    }
+
+    async fn rename_image(&mut self, images_path: PathBuf) -> Result<()> {
+        let (unformatted_image_file_name, image_file_name) = match (
+            self.unformatted_image_file_name.take(),
+            &self.image_file_name,
+        ) {
+            (Some(unformatted_image_file_name), Some(image_file_name)) => {
+                (unformatted_image_file_name, image_file_name)
+            }
+            _ => return Ok(()),
+        };
+
+        let mut old_path = images_path.clone();
+        old_path.push(unformatted_image_file_name);
+        let mut new_path = images_path;
+        new_path.push(image_file_name);
+        tokio::fs::rename(&old_path, new_path)
+            .await
+            .with_context(|| {
+                format!(
+                    "Could not rename image {} of question {}",
+                    old_path.display(),
+                    self.id
+                )
+            })?;
 
         Ok(())
     }
@@ -329,6 +606,25 @@ impl QuestionData {
             self.image_file_name.as_ref()?.as_os_str().to_string_lossy()
         ))
     }
+
+    pub fn image_path(&self, images_path: &Path) -> Option<PathBuf> {
+        let mut path = images_path.to_owned();
+        path.push(self.course_key.as_ref().expect("course key not set"));
+        path.push(
+            self.unformatted_image_file_name
+                .as_ref()
+                .or(self.image_file_name.as_ref())?,
+        );
+
+        Some(path)
+    }
+
+    pub fn image_data(&self, images_path: &Path) -> Option<Result<ImageData>> {
+        let key = self.full_image_path()?;
+        let path = self.image_path(images_path)?;
+
+        Some(ImageData::load(key, path))
+    }
 }
 
 impl Hashable for QuestionData {
@@ -349,12 +645,25 @@ impl Hashable for QuestionData {
         );
 
         bytes.extend(self.evaluation.as_bytes());
-        bytes.extend(self.source.as_bytes());
+        bytes.extend(self.source.as_str().as_bytes());
 
         if let Some(asked_at) = self.asked_at {
             bytes.extend(asked_at.to_string().as_bytes());
         }
 
+        if self.kind != QuestionKind::SingleChoice {
+            bytes.extend(self.kind.as_str().as_bytes());
+        }
+
+        for occurrence in &self.occurrences {
+            bytes.extend(occurrence.evaluation.as_bytes());
+            bytes.extend(occurrence.source.as_str().as_bytes());
+
+            if let Some(asked_at) = occurrence.asked_at {
+                bytes.extend(asked_at.to_string().as_bytes());
+            }
+        }
+
         bytes
     }
 
@@ -367,15 +676,28 @@ impl From<RawQuestionData> for QuestionData {
     fn from(raw: RawQuestionData) -> Self {
         let options = raw.options.into_iter().map(Into::into).collect();
 
-        Self::new(
-            raw.id.unwrap_or_else(|| Uuid::new_v4()),
-            raw.text,
-            raw.image,
-            options,
-            raw.evaluation,
-            raw.source,
-            raw.asked_at,
-        )
+        let mut data = Self {
+            id: raw.id.unwrap_or_else(Uuid::new_v4),
+            location: QuestionLocation {
+                id: raw.id,
+                ..Default::default()
+            },
+            course_key: None,
+            evaluation: raw.evaluation,
+            source: raw.source,
+            asked_at: raw.asked_at,
+            occurrences: raw.occurrences,
+            kind: raw.kind,
+            text: raw.text,
+            image_file_name: raw.image,
+            unformatted_image_file_name: None,
+            question_options: options,
+            hash: Default::default(),
+        };
+
+        data.set_hash();
+
+        data
     }
 }
 
@@ -445,7 +767,7 @@ impl Hashable for QuestionOptionData {
 impl From<RawQuestionOptionData> for QuestionOptionData {
     fn from(raw: RawQuestionOptionData) -> Self {
         Self::new(
-            raw.id.unwrap_or_else(|| Uuid::new_v4()),
+            raw.id.unwrap_or_else(Uuid::new_v4),
             raw.text,
             raw.correct.unwrap_or(false),
             raw.explanation,
@@ -453,11 +775,178 @@ impl From<RawQuestionOptionData> for QuestionOptionData {
     }
 }
 
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct QuestionOccurrenceData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asked_at: Option<NaiveDate>,
+    pub evaluation: String,
+    pub source: QuestionSource,
+}
+
+impl QuestionOccurrenceData {
+    fn eq_data(&self, other: &Self) -> bool {
+        self.evaluation == other.evaluation
+            && self.source == other.source
+            && (self.asked_at == other.asked_at
+                || self.asked_at.is_none()
+                || other.asked_at.is_none())
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Default, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct MergedIds {
+    #[serde(default)]
+    pub questions: BTreeMap<Uuid, Uuid>,
+    #[serde(default)]
+    pub question_options: BTreeMap<Uuid, Uuid>,
+}
+
+impl MergedIds {
+    pub fn is_empty(&self) -> bool {
+        self.questions.is_empty() && self.question_options.is_empty()
+    }
+
+    pub fn insert_question(&mut self, removed_id: Uuid, kept_id: Uuid) {
+        insert_merged_id(&mut self.questions, removed_id, kept_id);
+    }
+
+    pub fn insert_question_option(&mut self, removed_id: Uuid, kept_id: Uuid) {
+        insert_merged_id(&mut self.question_options, removed_id, kept_id);
+    }
+
+    pub fn extend(&mut self, other: &Self) {
+        for (removed_id, kept_id) in &other.questions {
+            self.insert_question(*removed_id, *kept_id);
+        }
+
+        for (removed_id, kept_id) in &other.question_options {
+            self.insert_question_option(*removed_id, *kept_id);
+        }
+    }
+
+    pub fn remove(&mut self, other: &Self) {
+        self.questions
+            .retain(|removed_id, kept_id| other.questions.get(removed_id) != Some(kept_id));
+        self.question_options
+            .retain(|removed_id, kept_id| other.question_options.get(removed_id) != Some(kept_id));
+    }
+}
+
+fn insert_merged_id(merged_ids: &mut BTreeMap<Uuid, Uuid>, removed_id: Uuid, kept_id: Uuid) {
+    for merged_id in merged_ids.values_mut() {
+        if *merged_id == removed_id {
+            *merged_id = kept_id;
+        }
+    }
+
+    merged_ids.insert(removed_id, kept_id);
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Clone, Copy, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct QuestionRules {
+    pub min_options: usize,
+    pub max_options: usize,
+}
+
+impl Default for QuestionRules {
+    fn default() -> Self {
+        Self {
+            min_options: 2,
+            max_options: 5,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Clone, Copy, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestionKind {
+    #[default]
+    SingleChoice,
+    MultipleChoice,
+}
+
+impl QuestionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SingleChoice => "single_choice",
+            Self::MultipleChoice => "multiple_choice",
+        }
+    }
+
+    pub fn is_single_choice(&self) -> bool {
+        *self == Self::SingleChoice
+    }
+}
+
+impl Display for QuestionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(
+    Serialize, Deserialize, JsonSchema, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy, Debug,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestionSource {
+    Partial,
+    FinalExam,
+    Retake,
+    Practice,
+    Textbook,
+    Other,
+}
+
+impl QuestionSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Partial => "partial",
+            Self::FinalExam => "final_exam",
+            Self::Retake => "retake",
+            Self::Practice => "practice",
+            Self::Textbook => "textbook",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl FromStr for QuestionSource {
+    type Err = anyhow::Error;
+
+    fn from_str(source: &str) -> Result<Self> {
+        QUESTION_SOURCES
+            .into_iter()
+            .find(|question_source| question_source.as_str() == source)
+            .ok_or_else(|| {
+                let sources: Vec<&str> = QUESTION_SOURCES
+                    .iter()
+                    .map(QuestionSource::as_str)
+                    .collect();
+
+                anyhow!(
+                    "unknown source `{source}`, expected one of {}",
+                    sources.join(", ")
+                )
+            })
+    }
+}
+
+impl Display for QuestionSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Hash, Eq, Clone, Debug)]
 pub struct CourseEvaluationData {
     pub course_key: Option<String>,
     pub key: String,
     pub name: String,
+    #[serde(default)]
+    pub sources: Vec<QuestionSource>,
     pub hash: String,
 }
 
@@ -467,6 +956,7 @@ impl CourseEvaluationData {
             course_key: None,
             key: raw.key,
             name: raw.name,
+            sources: raw.sources,
             hash: Default::default(),
         };
 
@@ -479,6 +969,10 @@ impl CourseEvaluationData {
         self.course_key = Some(course_key.clone());
     }
 
+    pub fn accepts(&self, source: QuestionSource) -> bool {
+        self.sources.is_empty() || self.sources.contains(&source)
+    }
+
     pub fn full_key(course_key: &str, key: &str) -> String {
         format!("{}{COURSE_EVALUATION_KEY_SEPARATOR}{}", course_key, key)
     }
@@ -489,6 +983,11 @@ impl Hashable for CourseEvaluationData {
         let mut bytes = vec![];
 
         bytes.extend(self.name.as_bytes());
+        bytes.extend(
+            self.sources
+                .iter()
+                .flat_map(|source| source.as_str().as_bytes()),
+        );
 
         bytes
     }
@@ -505,3 +1004,12 @@ impl From<RawCourseEvaluationData> for CourseEvaluationData {
 }
 
 pub const COURSE_EVALUATION_KEY_SEPARATOR: &str = "/";
+
+const QUESTION_SOURCES: [QuestionSource; 6] = [
+    QuestionSource::Partial,
+    QuestionSource::FinalExam,
+    QuestionSource::Retake,
+    QuestionSource::Practice,
+    QuestionSource::Textbook,
+    QuestionSource::Other,
+];
@@ -0,0 +1,208 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Rule {
+    InvalidCourseFile,
+    InvalidRules,
+    OptionCount,
+    CorrectOptionCount,
+    UnknownEvaluation,
+    UnusedEvaluation,
+    MissingImage,
+    InvalidImage,
+    DuplicateId,
+    CourseNameCollision,
+    SourceNotAccepted,
+}
+
+impl Rule {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::InvalidCourseFile => "invalid_course_file",
+            Self::InvalidRules => "invalid_rules",
+            Self::OptionCount => "option_count",
+            Self::CorrectOptionCount => "correct_option_count",
+            Self::UnknownEvaluation => "unknown_evaluation",
+            Self::UnusedEvaluation => "unused_evaluation",
+            Self::MissingImage => "missing_image",
+            Self::InvalidImage => "invalid_image",
+            Self::DuplicateId => "duplicate_id",
+            Self::CourseNameCollision => "course_name_collision",
+            Self::SourceNotAccepted => "source_not_accepted",
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::UnusedEvaluation => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Default, Clone, Debug)]
+pub struct QuestionLocation {
+    pub index: usize,
+    pub id: Option<Uuid>,
+    pub origin: Option<String>,
+}
+
+impl Display for QuestionLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "question {}", self.index + 1)?;
+
+        if let Some(id) = self.id {
+            write!(f, " ({id})")?;
+        }
+
+        if let Some(origin) = &self.origin {
+            write!(f, " from {origin}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    pub file: PathBuf,
+    pub question: Option<QuestionLocation>,
+    pub rule: Rule,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn severity(&self) -> Severity {
+        self.rule.severity()
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} [{}]",
+            self.file.display(),
+            self.severity(),
+            self.rule
+        )?;
+
+        if let Some(question) = &self.question {
+            write!(f, " {question}")?;
+        }
+
+        write!(f, ": {}", self.message)
+    }
+}
+
+#[derive(Default, Clone, Debug)]
+pub struct Diagnostics {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub fn push(
+        &mut self,
+        file: &Path,
+        question: Option<&QuestionLocation>,
+        rule: Rule,
+        message: impl Into<String>,
+    ) {
+        self.diagnostics.push(Diagnostic {
+            file: file.to_owned(),
+            question: question.cloned(),
+            rule,
+            message: message.into(),
+        });
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.iter()
+            .filter(|diagnostic| diagnostic.severity() == severity)
+            .count()
+    }
+
+    pub fn set_question_origins(&mut self, file: &Path, first_index: usize, origins: &[String]) {
+        for diagnostic in self
+            .diagnostics
+            .iter_mut()
+            .filter(|diagnostic| diagnostic.file == file)
+        {
+            if let Some(question) = &mut diagnostic.question {
+                question.origin = question
+                    .index
+                    .checked_sub(first_index)
+                    .and_then(|index| origins.get(index))
+                    .cloned();
+            }
+        }
+    }
+
+    pub fn into_result(self) -> Result<Self, Self> {
+        if self.count(Severity::Error) == 0 {
+            Ok(self)
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for diagnostic in &self.diagnostics {
+            writeln!(f, "{diagnostic}")?;
+        }
+
+        write!(
+            f,
+            "{} error(s), {} warning(s) found",
+            self.count(Severity::Error),
+            self.count(Severity::Warning)
+        )
+    }
+}
+
+impl Error for Diagnostics {}
@@ -0,0 +1,336 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{CourseData, CourseEvaluationData, QuestionData, QuestionOptionData, SyncSnapshot};
+
+#[derive(Serialize, PartialEq, Eq, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct FieldDiff {
+    pub field: &'static str,
+    pub engine: Value,
+    pub local: Value,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CourseDiff {
+    pub course_key: String,
+    pub change: ChangeKind,
+    pub fields: Vec<FieldDiff>,
+    pub evaluations: Vec<EvaluationDiff>,
+    pub questions: Vec<QuestionDiff>,
+}
+
+impl CourseDiff {
+    fn new(course_key: String, change: ChangeKind) -> Self {
+        Self {
+            course_key,
+            change,
+            fields: vec![],
+            evaluations: vec![],
+            questions: vec![],
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.fields.is_empty() && self.evaluations.is_empty() && self.questions.is_empty()
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct EvaluationDiff {
+    pub key: String,
+    pub change: ChangeKind,
+    pub fields: Vec<FieldDiff>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct QuestionDiff {
+    pub id: Uuid,
+    pub change: ChangeKind,
+    pub fields: Vec<FieldDiff>,
+    pub options: Vec<QuestionOptionDiff>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct QuestionOptionDiff {
+    pub id: Uuid,
+    pub change: ChangeKind,
+    pub fields: Vec<FieldDiff>,
+}
+
+pub fn diff_courses(courses_data: &[CourseData], snapshot: SyncSnapshot) -> Vec<CourseDiff> {
+    let mut engine_courses: BTreeMap<String, CourseData> = snapshot
+        .courses
+        .into_iter()
+        .map(|course| (course.key.clone(), course))
+        .collect();
+    let mut engine_evaluations: HashMap<String, CourseEvaluationData> = snapshot
+        .course_evaluations
+        .into_iter()
+        .filter_map(|evaluation| {
+            let full_key =
+                CourseEvaluationData::full_key(evaluation.course_key.as_deref()?, &evaluation.key);
+
+            Some((full_key, evaluation))
+        })
+        .collect();
+    let mut engine_questions: HashMap<Uuid, QuestionData> = snapshot
+        .questions
+        .into_iter()
+        .map(|question| (question.id, question))
+        .collect();
+    let mut engine_options: HashMap<Uuid, Vec<QuestionOptionData>> = HashMap::new();
+
+    for question_option in snapshot.question_options {
+        if let Some(question_id) = question_option.question_id {
+            engine_options
+                .entry(question_id)
+                .or_default()
+                .push(question_option);
+        }
+    }
+
+    let mut diffs = vec![];
+
+    for course in courses_data {
+        let mut course_diff = match engine_courses.remove(&course.key) {
+            Some(engine_course) => {
+                let mut course_diff = CourseDiff::new(course.key.clone(), ChangeKind::Changed);
+                course_diff.fields = course_fields_diff(&engine_course, course);
+
+                course_diff
+            }
+            None => CourseDiff::new(course.key.clone(), ChangeKind::Added),
+        };
+
+        for evaluation in &course.evaluations {
+            let full_key = CourseEvaluationData::full_key(&course.key, &evaluation.key);
+
+            match engine_evaluations.remove(&full_key) {
+                Some(engine_evaluation) => {
+                    let fields = field_diffs([
+                        (
+                            "name",
+                            json(&engine_evaluation.name),
+                            json(&evaluation.name),
+                        ),
+                        (
+                            "sources",
+                            json(&engine_evaluation.sources),
+                            json(&evaluation.sources),
+                        ),
+                    ]);
+
+                    if !fields.is_empty() {
+                        course_diff.evaluations.push(EvaluationDiff {
+                            key: evaluation.key.clone(),
+                            change: ChangeKind::Changed,
+                            fields,
+                        });
+                    }
+                }
+                None => course_diff.evaluations.push(EvaluationDiff {
+                    key: evaluation.key.clone(),
+                    change: ChangeKind::Added,
+                    fields: vec![],
+                }),
+            }
+        }
+
+        for question in &course.questions {
+            let question_diff = match engine_questions.remove(&question.id) {
+                Some(engine_question) => {
+                    let options = engine_options.remove(&question.id).unwrap_or_default();
+
+                    question_diff(&engine_question, options, question)
+                }
+                None => Some(QuestionDiff {
+                    id: question.id,
+                    change: ChangeKind::Added,
+                    fields: vec![],
+                    options: vec![],
+                }),
+            };
+
+            course_diff.questions.extend(question_diff);
+        }
+
+        if course_diff.change == ChangeKind::Added || !course_diff.is_empty() {
+            diffs.push(course_diff);
+        }
+    }
+
+    for course_key in engine_courses.into_keys() {
+        diffs.push(CourseDiff::new(course_key, ChangeKind::Removed));
+    }
+
+    for (_, evaluation) in engine_evaluations {
+        let course_diff = course_diff_entry(&mut diffs, evaluation.course_key.unwrap_or_default());
+
+        course_diff.evaluations.push(EvaluationDiff {
+            key: evaluation.key,
+            change: ChangeKind::Removed,
+            fields: vec![],
+        });
+    }
+
+    for (_, question) in engine_questions {
+        let course_diff = course_diff_entry(&mut diffs, question.course_key.unwrap_or_default());
+
+        course_diff.questions.push(QuestionDiff {
+            id: question.id,
+            change: ChangeKind::Removed,
+            fields: vec![],
+            options: vec![],
+        });
+    }
+
+    diffs.sort_by(|a, b| a.course_key.cmp(&b.course_key));
+
+    for course_diff in diffs.iter_mut() {
+        course_diff.evaluations.sort_by(|a, b| a.key.cmp(&b.key));
+        course_diff
+            .questions
+            .sort_by_key(|question_diff| question_diff.id);
+    }
+
+    diffs
+}
+
+fn course_diff_entry(diffs: &mut Vec<CourseDiff>, course_key: String) -> &mut CourseDiff {
+    match diffs.iter().position(|diff| diff.course_key == course_key) {
+        Some(index) => &mut diffs[index],
+        None => {
+            diffs.push(CourseDiff::new(course_key, ChangeKind::Changed));
+
+            diffs.last_mut().unwrap()
+        }
+    }
+}
+
+fn course_fields_diff(engine: &CourseData, local: &CourseData) -> Vec<FieldDiff> {
+    field_diffs([
+        ("name", json(&engine.name), json(&local.name)),
+        (
+            "short_name",
+            json(&engine.short_name),
+            json(&local.short_name),
+        ),
+        ("aliases", json(&engine.aliases), json(&local.aliases)),
+        ("year", json(&engine.year), json(&local.year)),
+    ])
+}
+
+fn question_diff(
+    engine: &QuestionData,
+    engine_options: Vec<QuestionOptionData>,
+    local: &QuestionData,
+) -> Option<QuestionDiff> {
+    let fields = field_diffs([
+        ("text", json(&engine.text), json(&local.text)),
+        (
+            "evaluation",
+            json(&engine.evaluation),
+            json(&local.evaluation),
+        ),
+        ("source", json(&engine.source), json(&local.source)),
+        ("asked_at", json(&engine.asked_at), json(&local.asked_at)),
+        (
+            "occurrences",
+            json(&engine.occurrences),
+            json(&local.occurrences),
+        ),
+        ("kind", json(&engine.kind), json(&local.kind)),
+        (
+            "image",
+            json(&engine.image_file_name),
+            json(&local.image_file_name),
+        ),
+    ]);
+
+    let mut engine_options: HashMap<Uuid, QuestionOptionData> = engine_options
+        .into_iter()
+        .map(|question_option| (question_option.id, question_option))
+        .collect();
+    let mut options = vec![];
+
+    for question_option in &local.question_options {
+        match engine_options.remove(&question_option.id) {
+            Some(engine_option) => {
+                let fields = question_option_fields_diff(&engine_option, question_option);
+
+                if !fields.is_empty() {
+                    options.push(QuestionOptionDiff {
+                        id: question_option.id,
+                        change: ChangeKind::Changed,
+                        fields,
+                    });
+                }
+            }
+            None => options.push(QuestionOptionDiff {
+                id: question_option.id,
+                change: ChangeKind::Added,
+                fields: vec![],
+            }),
+        }
+    }
+
+    options.extend(engine_options.into_keys().map(|id| QuestionOptionDiff {
+        id,
+        change: ChangeKind::Removed,
+        fields: vec![],
+    }));
+    options.sort_by_key(|option_diff| option_diff.id);
+
+    if fields.is_empty() && options.is_empty() {
+        return None;
+    }
+
+    Some(QuestionDiff {
+        id: local.id,
+        change: ChangeKind::Changed,
+        fields,
+        options,
+    })
+}
+
+fn question_option_fields_diff(
+    engine: &QuestionOptionData,
+    local: &QuestionOptionData,
+) -> Vec<FieldDiff> {
+    field_diffs([
+        ("text", json(&engine.text), json(&local.text)),
+        ("correct", json(&engine.correct), json(&local.correct)),
+        (
+            "explanation",
+            json(&engine.explanation),
+            json(&local.explanation),
+        ),
+    ])
+}
+
+fn field_diffs<const N: usize>(fields: [(&'static str, Value, Value); N]) -> Vec<FieldDiff> {
+    fields
+        .into_iter()
+        .filter(|(_, engine, local)| engine != local)
+        .map(|(field, engine, local)| FieldDiff {
+            field,
+            engine,
+            local,
+        })
+        .collect()
+}
+
+fn json<T: Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or_default()
+}
@@ -0,0 +1,142 @@
+use std::cmp::Ordering;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{helpers::normalize_text, CourseData, QuestionData};
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DuplicateCandidate {
+    pub score: f64,
+    pub questions: [DuplicateQuestion; 2],
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DuplicateQuestion {
+    pub id: Uuid,
+    pub course_key: String,
+    pub evaluation: String,
+    pub asked_at: Option<NaiveDate>,
+    pub text: String,
+}
+
+impl DuplicateQuestion {
+    fn new(course_key: &str, question: &QuestionData) -> Self {
+        Self {
+            id: question.id,
+            course_key: course_key.to_owned(),
+            evaluation: question.evaluation.clone(),
+            asked_at: question.asked_at,
+            text: question.text.clone(),
+        }
+    }
+}
+
+struct Fingerprint<'a> {
+    course_key: &'a str,
+    question: &'a QuestionData,
+    bigrams: Vec<(char, char)>,
+}
+
+pub fn find_duplicates(courses_data: &[CourseData], threshold: f64) -> Vec<DuplicateCandidate> {
+    let fingerprints: Vec<Fingerprint> = courses_data
+        .iter()
+        .flat_map(|course| {
+            course.questions.iter().map(|question| Fingerprint {
+                course_key: &course.key,
+                question,
+                bigrams: bigrams(&comparable_text(question)),
+            })
+        })
+        .collect();
+
+    let mut candidates = vec![];
+
+    for (index, a) in fingerprints.iter().enumerate() {
+        for b in &fingerprints[index + 1..] {
+            let score = similarity(&a.bigrams, &b.bigrams);
+
+            if score >= threshold {
+                candidates.push(DuplicateCandidate {
+                    score,
+                    questions: [
+                        DuplicateQuestion::new(a.course_key, a.question),
+                        DuplicateQuestion::new(b.course_key, b.question),
+                    ],
+                });
+            }
+        }
+    }
+
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+    candidates
+}
+
+pub fn normalize_question_text(text: &str) -> String {
+    let text: String = text
+        .chars()
+        .map(|character| {
+            if character.is_alphanumeric() {
+                character
+            } else {
+                ' '
+            }
+        })
+        .collect();
+
+    normalize_text(&text)
+}
+
+fn comparable_text(question: &QuestionData) -> String {
+    let mut options: Vec<String> = question
+        .question_options
+        .iter()
+        .map(|question_option| normalize_question_text(&question_option.text))
+        .collect();
+    options.sort();
+
+    let mut text = normalize_question_text(&question.text);
+
+    for option in options {
+        text.push(' ');
+        text.push_str(&option);
+    }
+
+    text
+}
+
+fn bigrams(text: &str) -> Vec<(char, char)> {
+    let characters: Vec<char> = text.chars().collect();
+    let mut bigrams: Vec<(char, char)> = characters
+        .windows(2)
+        .map(|window| (window[0], window[1]))
+        .collect();
+    bigrams.sort_unstable();
+
+    bigrams
+}
+
+fn similarity(a: &[(char, char)], b: &[(char, char)]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+
+    let mut shared = 0;
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                shared += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+
+    (2 * shared) as f64 / (a.len() + b.len()) as f64
+}
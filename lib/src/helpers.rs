@@ -1,9 +1,16 @@
+use std::collections::{BTreeMap, HashSet};
 use std::fs::{self, DirEntry, ReadDir};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{bail, Result};
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+use uuid::Uuid;
 
-use crate::CourseData;
+use crate::{
+    checks::check_courses,
+    diagnostics::{Diagnostics, Rule},
+    CourseData, MergedIds, RawCourseData,
+};
 
 pub fn read_data_dir(data_path: PathBuf) -> Result<ReadDir> {
     let data_path = fs::canonicalize(data_path)?;
@@ -26,16 +33,100 @@ pub fn write_data(path: PathBuf, data: String) -> Result<()> {
     Ok(())
 }
 
+pub fn normalize_text(text: &str) -> String {
+    text.nfd()
+        .filter(|character| !is_combining_mark(*character))
+        .collect::<String>()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[derive(Default, Debug)]
+pub struct LoadedCourses {
+    pub courses_data: Vec<CourseData>,
+    pub warnings: Diagnostics,
+    pub merged_ids: BTreeMap<PathBuf, MergedIds>,
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 pub async fn load_courses_data_and_write_formatted(
     data_path: PathBuf,
     images_path: PathBuf,
-) -> Result<Vec<CourseData>> {
+    known_ids: &HashSet<Uuid>,
+) -> Result<LoadedCourses> {
+    let mut loaded_courses = load_courses_data(data_path, &images_path, known_ids)?;
+
+    for course_data in loaded_courses.courses_data.iter_mut() {
+        course_data.write_formatted(images_path.clone()).await?;
+    }
+
+    Ok(loaded_courses)
+}
+
+pub fn load_courses_data(
+    data_path: PathBuf,
+    images_path: &Path,
+    known_ids: &HashSet<Uuid>,
+) -> Result<LoadedCourses> {
     let mut courses_data = vec![];
+    let mut diagnostics = Diagnostics::default();
+
+    for dir_entry in read_data_dir(data_path)? {
+        let dir_entry = dir_entry?;
+        let path = dir_entry.path();
+
+        match CourseData::load(path.clone(), dir_entry) {
+            Ok(course_data) => {
+                course_data.check(images_path, &mut diagnostics);
+                courses_data.push(course_data);
+            }
+            Err(error) => {
+                diagnostics.push(&path, None, Rule::InvalidCourseFile, format!("{error:#}"))
+            }
+        }
+    }
+
+    check_courses(&courses_data, &mut diagnostics);
 
+    let warnings = diagnostics.into_result()?;
+    let mut merged_ids = BTreeMap::new();
+
+    for course_data in courses_data.iter_mut() {
+        let course_merged_ids = course_data.prepare(known_ids);
+
+        if !course_merged_ids.is_empty() {
+            merged_ids.insert(course_data.path.clone(), course_merged_ids);
+        }
+    }
+
+    Ok(LoadedCourses {
+        courses_data,
+        warnings,
+        merged_ids,
+    })
+}
+
+pub fn remove_synced_merged_ids(data_path: PathBuf, synced_merged_ids: &MergedIds) -> Result<()> {
     for dir_entry in read_data_dir(data_path)? {
-        courses_data
-            .push(CourseData::load_and_write_formatted(dir_entry?, images_path.clone()).await?);
+        let dir_entry = dir_entry?;
+        let path = dir_entry.path();
+        let mut raw_course_data = RawCourseData::from_slice(&read_dir_entry_data(dir_entry)?)?;
+
+        if raw_course_data.merged_ids.is_empty() {
+            continue;
+        }
+
+        raw_course_data.merged_ids.remove(synced_merged_ids);
+        write_data(path, serde_json::to_string_pretty(&raw_course_data)?)?;
     }
 
-    Ok(courses_data)
+    Ok(())
 }
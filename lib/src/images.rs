@@ -0,0 +1,88 @@
+use std::fs;
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ImageFormat {
+    Avif,
+    Gif,
+    Jpeg,
+    Png,
+    Svg,
+    Webp,
+}
+
+impl ImageFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension().and_then(OsStr::to_str)?;
+
+        Self::from_extension(extension)
+    }
+
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "avif" => Some(Self::Avif),
+            "gif" => Some(Self::Gif),
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "png" => Some(Self::Png),
+            "svg" => Some(Self::Svg),
+            "webp" => Some(Self::Webp),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Avif => "image/avif",
+            Self::Gif => "image/gif",
+            Self::Jpeg => "image/jpeg",
+            Self::Png => "image/png",
+            Self::Svg => "image/svg+xml",
+            Self::Webp => "image/webp",
+        }
+    }
+
+    pub fn matches(&self, content: &[u8]) -> bool {
+        match self {
+            Self::Avif => {
+                content.get(4..8) == Some(b"ftyp")
+                    && content
+                        .get(8..content.len().min(64))
+                        .map_or(false, |brands| {
+                            brands
+                                .chunks(4)
+                                .any(|brand| brand == b"avif" || brand == b"avis")
+                        })
+            }
+            Self::Gif => content.starts_with(b"GIF87a") || content.starts_with(b"GIF89a"),
+            Self::Jpeg => content.starts_with(&[0xFF, 0xD8, 0xFF]),
+            Self::Png => content.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]),
+            Self::Svg => {
+                String::from_utf8_lossy(&content[..content.len().min(1024)]).contains("<svg")
+            }
+            Self::Webp => content.starts_with(b"RIFF") && content.get(8..12) == Some(b"WEBP"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ImageData {
+    pub key: String,
+    pub hash: String,
+
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+impl ImageData {
+    pub fn load(key: String, path: PathBuf) -> Result<Self> {
+        let hash = blake3::hash(&fs::read(&path)?).to_string();
+
+        Ok(Self { key, hash, path })
+    }
+}
@@ -1,10 +1,30 @@
+mod checks;
+mod csv_import;
 mod data;
+mod diagnostics;
+mod diff;
+mod duplicates;
 mod hashable;
 mod helpers;
+mod images;
+mod moodle_export;
+mod moodle_import;
 mod raw_data;
+mod schema;
 mod sync;
+mod transcript_import;
 
+pub use checks::*;
+pub use csv_import::*;
 pub use data::*;
+pub use diagnostics::*;
+pub use diff::*;
+pub use duplicates::*;
 pub use helpers::*;
+pub use images::*;
+pub use moodle_export::*;
+pub use moodle_import::*;
 pub use raw_data::*;
+pub use schema::*;
 pub use sync::*;
+pub use transcript_import::*;
@@ -0,0 +1,201 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::{helpers::escape_html, CourseData, QuestionData, QuestionKind, QuestionOptionData};
+
+pub fn moodle_xml(course_data: &CourseData, images_path: &Path) -> Result<String> {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<quiz>\n");
+    let mut category = None;
+
+    for question in &course_data.questions {
+        if category != Some(&question.evaluation) {
+            category = Some(&question.evaluation);
+
+            writeln!(
+                xml,
+                "  <question type=\"category\">\n    <category>\n      <text>{}</text>\n    </category>\n  </question>",
+                escape_html(&category_path(course_data, question))
+            )?;
+        }
+
+        let mut question_text = escape_html(&question.text);
+        let mut files = String::new();
+
+        if let Some(image_path) = question.image_path(images_path) {
+            let file_name = question
+                .image_file_name
+                .as_ref()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned();
+            let content = fs::read(&image_path)
+                .with_context(|| format!("Could not read image {}", image_path.display()))?;
+
+            write!(
+                question_text,
+                "<p><img src=\"@@PLUGINFILE@@/{}\"></p>",
+                escape_html(&file_name)
+            )?;
+            write!(
+                files,
+                "\n      <file name=\"{}\" path=\"/\" encoding=\"base64\">{}</file>",
+                escape_html(&file_name),
+                base64::encode(content)
+            )?;
+        }
+
+        writeln!(xml, "  <question type=\"multichoice\">")?;
+        writeln!(
+            xml,
+            "    <name>\n      <text>{}</text>\n    </name>",
+            escape_html(&question_name(question))
+        )?;
+        writeln!(
+            xml,
+            "    <questiontext format=\"html\">\n      <text>{}</text>{files}\n    </questiontext>",
+            escape_html(&question_text)
+        )?;
+        writeln!(xml, "    <idnumber>{}</idnumber>", question.id)?;
+        writeln!(xml, "    <defaultgrade>1</defaultgrade>")?;
+        writeln!(
+            xml,
+            "    <single>{}</single>",
+            question.kind == QuestionKind::SingleChoice
+        )?;
+        writeln!(xml, "    <shuffleanswers>true</shuffleanswers>")?;
+        writeln!(xml, "    <answernumbering>abc</answernumbering>")?;
+
+        for question_option in &question.question_options {
+            writeln!(
+                xml,
+                "    <answer fraction=\"{}\" format=\"html\">\n      <text>{}</text>",
+                fraction(question, question_option),
+                escape_html(&escape_html(&question_option.text))
+            )?;
+
+            if let Some(explanation) = &question_option.explanation {
+                writeln!(
+                    xml,
+                    "      <feedback format=\"html\">\n        <text>{}</text>\n      </feedback>",
+                    escape_html(&escape_html(explanation))
+                )?;
+            }
+
+            writeln!(xml, "    </answer>")?;
+        }
+
+        writeln!(xml, "  </question>")?;
+    }
+
+    xml.push_str("</quiz>");
+
+    Ok(xml)
+}
+
+pub fn gift(course_data: &CourseData) -> Result<String> {
+    let mut gift = String::new();
+    let mut category = None;
+
+    for question in &course_data.questions {
+        if category != Some(&question.evaluation) {
+            category = Some(&question.evaluation);
+
+            writeln!(
+                gift,
+                "$CATEGORY: {}\n",
+                category_path(course_data, question)
+            )?;
+        }
+
+        writeln!(
+            gift,
+            "::{}::[html]{} {{",
+            escape_gift(&question_name(question)),
+            escape_gift(&escape_html(&question.text))
+        )?;
+
+        for question_option in &question.question_options {
+            let prefix = match question.kind {
+                QuestionKind::SingleChoice if question_option.correct => "=".to_owned(),
+                QuestionKind::SingleChoice => "~".to_owned(),
+                QuestionKind::MultipleChoice => {
+                    format!("~%{}%", fraction(question, question_option))
+                }
+            };
+
+            write!(
+                gift,
+                "  {prefix}{}",
+                escape_gift(&escape_html(&question_option.text))
+            )?;
+
+            if let Some(explanation) = &question_option.explanation {
+                write!(gift, "#{}", escape_gift(&escape_html(explanation)))?;
+            }
+
+            writeln!(gift)?;
+        }
+
+        writeln!(gift, "}}\n")?;
+    }
+
+    Ok(gift)
+}
+
+fn category_path(course_data: &CourseData, question: &QuestionData) -> String {
+    let evaluation_name = course_data
+        .evaluations
+        .iter()
+        .find(|evaluation| evaluation.key == question.evaluation)
+        .map_or(&question.evaluation, |evaluation| &evaluation.name);
+
+    format!("$course$/top/{}/{evaluation_name}", course_data.name)
+}
+
+fn question_name(question: &QuestionData) -> String {
+    const MAX_NAME_LENGTH: usize = 60;
+
+    let mut name: String = question.text.chars().take(MAX_NAME_LENGTH).collect();
+
+    if name.len() < question.text.len() {
+        name.push('…');
+    }
+
+    name
+}
+
+fn fraction(question: &QuestionData, question_option: &QuestionOptionData) -> String {
+    let count = question
+        .question_options
+        .iter()
+        .filter(|other_option| other_option.correct == question_option.correct)
+        .count();
+
+    let fraction = match (question.kind, question_option.correct) {
+        (_, true) => 100.0 / count as f64,
+        (QuestionKind::SingleChoice, false) => 0.0,
+        (QuestionKind::MultipleChoice, false) => -100.0 / count as f64,
+    };
+
+    format!("{fraction:.5}")
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_owned()
+}
+
+fn escape_gift(text: &str) -> String {
+    let mut escaped = String::new();
+
+    for character in text.chars() {
+        if matches!(character, '~' | '=' | '#' | '{' | '}' | ':') {
+            escaped.push('\\');
+        }
+
+        escaped.push(character);
+    }
+
+    escaped.replace('\n', "\\n")
+}
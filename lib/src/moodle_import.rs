@@ -0,0 +1,248 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use roxmltree::{Document, Node};
+use uuid::Uuid;
+
+use crate::{
+    helpers::normalize_text, ImageFormat, QuestionKind, QuestionOccurrenceData,
+    RawCourseEvaluationData, RawQuestionData, RawQuestionOptionData,
+};
+
+#[derive(Clone, Debug)]
+pub struct ImportedImage {
+    pub file_name: PathBuf,
+    pub content: Vec<u8>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SkippedQuestion {
+    pub name: String,
+    pub reason: String,
+}
+
+#[derive(Default, Debug)]
+pub struct MoodleImport {
+    pub questions: Vec<RawQuestionData>,
+    pub question_names: Vec<String>,
+    pub images: Vec<ImportedImage>,
+    pub skipped_questions: Vec<SkippedQuestion>,
+}
+
+impl MoodleImport {
+    pub fn parse(
+        xml: &str,
+        evaluations: &[RawCourseEvaluationData],
+        occurrence: QuestionOccurrenceData,
+    ) -> Result<Self> {
+        let document = Document::parse(xml).context("Invalid Moodle XML")?;
+        let mut moodle_import = Self::default();
+        let mut evaluation = occurrence.evaluation.clone();
+
+        for question in document
+            .root_element()
+            .children()
+            .filter(|node| node.has_tag_name("question"))
+        {
+            let name = child_text(question, &["name", "text"]).unwrap_or_default();
+
+            match question.attribute("type") {
+                Some("category") => {
+                    let category = child_text(question, &["category", "text"]).unwrap_or_default();
+
+                    evaluation = category_evaluation(&category, evaluations)
+                        .unwrap_or_else(|| occurrence.evaluation.clone());
+                }
+                Some("multichoice") => {
+                    let occurrence = QuestionOccurrenceData {
+                        evaluation: evaluation.clone(),
+                        ..occurrence.clone()
+                    };
+
+                    match moodle_import.push_question(question, occurrence) {
+                        Ok(()) => moodle_import.question_names.push(name),
+                        Err(error) => moodle_import.skipped_questions.push(SkippedQuestion {
+                            name,
+                            reason: format!("{error:#}"),
+                        }),
+                    }
+                }
+                question_type => moodle_import.skipped_questions.push(SkippedQuestion {
+                    name,
+                    reason: format!(
+                        "unsupported question type `{}`",
+                        question_type.unwrap_or_default()
+                    ),
+                }),
+            }
+        }
+
+        Ok(moodle_import)
+    }
+
+    fn push_question(&mut self, question: Node, occurrence: QuestionOccurrenceData) -> Result<()> {
+        let id = Uuid::new_v4();
+        let question_text = question
+            .children()
+            .find(|node| node.has_tag_name("questiontext"));
+        let text = question_text
+            .and_then(|node| child_text(node, &["text"]))
+            .map(|text| html_to_text(&text))
+            .unwrap_or_default();
+
+        if text.is_empty() {
+            bail!("question has no text");
+        }
+
+        let mut image = None;
+
+        for file in question_text
+            .into_iter()
+            .flat_map(|node| node.children())
+            .filter(|node| node.has_tag_name("file"))
+        {
+            if image.is_some() {
+                bail!("question has more than one image");
+            }
+
+            let file_name = PathBuf::from(file.attribute("name").unwrap_or_default());
+            let image_format = ImageFormat::from_path(&file_name).with_context(|| {
+                format!(
+                    "image `{}` has an unsupported extension",
+                    file_name.display()
+                )
+            })?;
+            let content = base64::decode(
+                file.text()
+                    .unwrap_or_default()
+                    .split_whitespace()
+                    .collect::<String>(),
+            )
+            .with_context(|| format!("image `{}` is not valid base64", file_name.display()))?;
+
+            if !image_format.matches(&content) {
+                bail!(
+                    "image `{}` is not a valid {} file",
+                    file_name.display(),
+                    image_format.content_type()
+                );
+            }
+
+            let mut new_file_name = PathBuf::from(id.to_string());
+            new_file_name.set_extension(file_name.extension().unwrap_or_default());
+
+            image = Some(ImportedImage {
+                file_name: new_file_name,
+                content,
+            });
+        }
+
+        let single = child_text(question, &["single"]).map_or(true, |single| single == "true");
+        let options = question
+            .children()
+            .filter(|node| node.has_tag_name("answer"))
+            .map(|answer| {
+                let fraction: f64 = answer
+                    .attribute("fraction")
+                    .unwrap_or("0")
+                    .parse()
+                    .unwrap_or_default();
+
+                RawQuestionOptionData {
+                    id: None,
+                    text: child_text(answer, &["text"])
+                        .map(|text| html_to_text(&text))
+                        .unwrap_or_default(),
+                    correct: if fraction > 0.0 { Some(true) } else { None },
+                    explanation: child_text(answer, &["feedback", "text"])
+                        .map(|text| html_to_text(&text))
+                        .filter(|explanation| !explanation.is_empty()),
+                }
+            })
+            .collect();
+
+        self.questions.push(RawQuestionData {
+            id: Some(id),
+            evaluation: occurrence.evaluation,
+            source: occurrence.source,
+            asked_at: occurrence.asked_at,
+            occurrences: vec![],
+            kind: if single {
+                QuestionKind::SingleChoice
+            } else {
+                QuestionKind::MultipleChoice
+            },
+            text,
+            image: image.as_ref().map(|image| image.file_name.clone()),
+            options,
+        });
+        self.images.extend(image);
+
+        Ok(())
+    }
+}
+
+fn child_text(node: Node, path: &[&str]) -> Option<String> {
+    let mut node = node;
+
+    for tag_name in path {
+        node = node
+            .children()
+            .find(|child| child.has_tag_name(*tag_name))?;
+    }
+
+    Some(node.text().unwrap_or_default().trim().to_owned())
+}
+
+fn category_evaluation(category: &str, evaluations: &[RawCourseEvaluationData]) -> Option<String> {
+    let category_name = normalize_text(category.rsplit('/').next()?);
+
+    evaluations
+        .iter()
+        .find(|evaluation| {
+            normalize_text(&evaluation.name) == category_name
+                || normalize_text(&evaluation.key) == category_name
+        })
+        .map(|evaluation| evaluation.key.clone())
+}
+
+fn html_to_text(html: &str) -> String {
+    const BLOCK_TAGS: [&str; 11] = [
+        "p", "br", "div", "li", "tr", "h1", "h2", "h3", "h4", "h5", "h6",
+    ];
+
+    let mut text = String::new();
+    let mut tag = None;
+
+    for character in html.chars() {
+        match (&mut tag, character) {
+            (None, '<') => tag = Some(String::new()),
+            (Some(tag_content), '>') => {
+                let tag_name = tag_content
+                    .trim_start_matches('/')
+                    .split(|character: char| character.is_whitespace() || character == '/')
+                    .next()
+                    .unwrap_or_default()
+                    .to_lowercase();
+
+                if BLOCK_TAGS.contains(&tag_name.as_str()) {
+                    text.push(' ');
+                }
+
+                tag = None;
+            }
+            (Some(tag_content), _) => tag_content.push(character),
+            (None, _) => text.push(character),
+        }
+    }
+
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -2,25 +2,33 @@ use std::path::PathBuf;
 
 use anyhow::Result;
 use chrono::NaiveDate;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::{CourseData, CourseEvaluationData, QuestionData, QuestionOptionData};
+use crate::{
+    CourseData, CourseEvaluationData, MergedIds, QuestionData, QuestionKind,
+    QuestionOccurrenceData, QuestionOptionData, QuestionRules, QuestionSource,
+};
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct RawCourseData {
     pub name: String,
     pub short_name: String,
     pub aliases: Vec<String>,
     pub year: Option<i16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rules: Option<QuestionRules>,
     pub questions: Vec<RawQuestionData>,
     pub evaluations: Vec<RawCourseEvaluationData>,
+    #[serde(default, skip_serializing_if = "MergedIds::is_empty")]
+    pub merged_ids: MergedIds,
 }
 
 impl RawCourseData {
     pub fn from_slice(raw_data: &[u8]) -> Result<Self> {
-        Ok(serde_json::from_slice(&raw_data)?)
+        Ok(serde_json::from_slice(raw_data)?)
     }
 }
 
@@ -34,21 +42,31 @@ impl From<CourseData> for RawCourseData {
             short_name: data.short_name,
             aliases: data.aliases,
             year: data.year,
+            rules: if data.rules == QuestionRules::default() {
+                None
+            } else {
+                Some(data.rules)
+            },
             questions: raw_questions,
             evaluations: raw_evaluations,
+            merged_ids: data.merged_ids,
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct RawQuestionData {
     pub id: Option<Uuid>,
 
     pub evaluation: String,
-    pub source: String,
+    pub source: QuestionSource,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub asked_at: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub occurrences: Vec<QuestionOccurrenceData>,
+    #[serde(default, skip_serializing_if = "QuestionKind::is_single_choice")]
+    pub kind: QuestionKind,
     pub text: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub image: Option<PathBuf>,
@@ -66,12 +84,14 @@ impl From<QuestionData> for RawQuestionData {
             options: raw_question_options,
             evaluation: data.evaluation,
             asked_at: data.asked_at,
+            occurrences: data.occurrences,
+            kind: data.kind,
             source: data.source,
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 pub struct RawQuestionOptionData {
     pub id: Option<Uuid>,
 
@@ -97,10 +117,12 @@ impl From<QuestionOptionData> for RawQuestionOptionData {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 pub struct RawCourseEvaluationData {
     pub key: String,
     pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<QuestionSource>,
 }
 
 impl From<CourseEvaluationData> for RawCourseEvaluationData {
@@ -108,6 +130,7 @@ impl From<CourseEvaluationData> for RawCourseEvaluationData {
         Self {
             key: data.key,
             name: data.name,
+            sources: data.sources,
         }
     }
 }
@@ -0,0 +1,32 @@
+use schemars::{
+    schema::{RootSchema, Schema},
+    schema_for,
+};
+
+use crate::{QuestionRules, RawCourseData};
+
+pub fn course_data_schema(rules: &[QuestionRules]) -> RootSchema {
+    let mut schema = schema_for!(RawCourseData);
+
+    let default_rules = QuestionRules::default();
+    let min_options = rules
+        .iter()
+        .map(|rules| rules.min_options)
+        .fold(default_rules.min_options, usize::min);
+    let max_options = rules
+        .iter()
+        .map(|rules| rules.max_options)
+        .fold(default_rules.max_options, usize::max);
+
+    if let Some(Schema::Object(question_schema)) = schema.definitions.get_mut("RawQuestionData") {
+        if let Some(Schema::Object(options_schema)) =
+            question_schema.object().properties.get_mut("options")
+        {
+            let array = options_schema.array();
+            array.min_items = Some(min_options as u32);
+            array.max_items = Some(max_options as u32);
+        }
+    }
+
+    schema
+}
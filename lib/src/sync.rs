@@ -1,11 +1,13 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use super::{CourseData, CourseEvaluationData, QuestionData, QuestionOptionData};
+use super::{
+    CourseData, CourseEvaluationData, ImageData, MergedIds, QuestionData, QuestionOptionData,
+};
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
 pub struct SyncData {
     pub courses_to_sync: Vec<CourseData>,
     pub courses_to_delete: Vec<String>,
@@ -18,6 +20,138 @@ pub struct SyncData {
 
     pub course_evaluations_to_sync: Vec<CourseEvaluationData>,
     pub course_evaluations_to_delete: Vec<String>,
+
+    pub images_to_sync: Vec<ImageData>,
+    pub images_to_delete: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "MergedIds::is_empty")]
+    pub merged_ids: MergedIds,
+}
+
+impl SyncData {
+    pub fn into_batches(self, batch_size: usize) -> Vec<SyncBatch> {
+        let mut batches = vec![];
+
+        push_batches(
+            &mut batches,
+            self.courses_to_sync,
+            batch_size,
+            "course(s) to sync",
+            |data, items| data.courses_to_sync = items,
+        );
+        push_batches(
+            &mut batches,
+            self.course_evaluations_to_sync,
+            batch_size,
+            "course evaluation(s) to sync",
+            |data, items| data.course_evaluations_to_sync = items,
+        );
+        push_batches(
+            &mut batches,
+            self.questions_to_sync,
+            batch_size,
+            "question(s) to sync",
+            |data, items| data.questions_to_sync = items,
+        );
+        push_batches(
+            &mut batches,
+            self.question_options_to_sync,
+            batch_size,
+            "question option(s) to sync",
+            |data, items| data.question_options_to_sync = items,
+        );
+        push_batches(
+            &mut batches,
+            self.images_to_sync,
+            batch_size,
+            "image(s) to sync",
+            |data, items| data.images_to_sync = items,
+        );
+
+        if !self.merged_ids.is_empty() {
+            batches.push(SyncBatch {
+                id: Uuid::new_v4(),
+                description: format!(
+                    "{} merged question(s) and {} merged question option(s)",
+                    self.merged_ids.questions.len(),
+                    self.merged_ids.question_options.len()
+                ),
+                data: SyncData {
+                    merged_ids: self.merged_ids,
+                    ..Default::default()
+                },
+            });
+        }
+
+        push_batches(
+            &mut batches,
+            self.images_to_delete,
+            batch_size,
+            "image(s) to delete",
+            |data, items| data.images_to_delete = items,
+        );
+        push_batches(
+            &mut batches,
+            self.question_options_to_delete,
+            batch_size,
+            "question option(s) to delete",
+            |data, items| data.question_options_to_delete = items,
+        );
+        push_batches(
+            &mut batches,
+            self.questions_to_delete,
+            batch_size,
+            "question(s) to delete",
+            |data, items| data.questions_to_delete = items,
+        );
+        push_batches(
+            &mut batches,
+            self.course_evaluations_to_delete,
+            batch_size,
+            "course evaluation(s) to delete",
+            |data, items| data.course_evaluations_to_delete = items,
+        );
+        push_batches(
+            &mut batches,
+            self.courses_to_delete,
+            batch_size,
+            "course(s) to delete",
+            |data, items| data.courses_to_delete = items,
+        );
+
+        batches
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SyncBatch {
+    pub id: Uuid,
+    pub description: String,
+    pub data: SyncData,
+}
+
+fn push_batches<T>(
+    batches: &mut Vec<SyncBatch>,
+    items: Vec<T>,
+    batch_size: usize,
+    description: &str,
+    set_items: impl Fn(&mut SyncData, Vec<T>),
+) {
+    let mut items = items.into_iter().peekable();
+
+    while items.peek().is_some() {
+        let chunk: Vec<T> = items.by_ref().take(batch_size.max(1)).collect();
+
+        let mut data = SyncData::default();
+        let description = format!("{} {description}", chunk.len());
+        set_items(&mut data, chunk);
+
+        batches.push(SyncBatch {
+            id: Uuid::new_v4(),
+            description,
+            data,
+        });
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -26,5 +160,38 @@ pub struct SyncMetadata {
     pub questions_metadata: HashMap<Uuid, String>,
     pub question_options_metadata: HashMap<Uuid, String>,
     pub course_evaluations_metadata: HashMap<String, String>,
+    pub images_metadata: HashMap<String, String>,
     pub images_bucket_name: String,
 }
+
+impl SyncMetadata {
+    pub fn known_ids(&self) -> HashSet<Uuid> {
+        self.questions_metadata
+            .keys()
+            .chain(self.question_options_metadata.keys())
+            .copied()
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SyncSnapshot {
+    pub courses: Vec<CourseData>,
+    pub questions: Vec<QuestionData>,
+    pub question_options: Vec<QuestionOptionData>,
+    pub course_evaluations: Vec<CourseEvaluationData>,
+}
+
+impl SyncSnapshot {
+    pub fn known_ids(&self) -> HashSet<Uuid> {
+        self.questions
+            .iter()
+            .map(|question| question.id)
+            .chain(
+                self.question_options
+                    .iter()
+                    .map(|question_option| question_option.id),
+            )
+            .collect()
+    }
+}
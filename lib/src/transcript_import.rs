@@ -0,0 +1,421 @@
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+
+use crate::{QuestionKind, QuestionOccurrenceData, RawQuestionData, RawQuestionOptionData};
+
+#[derive(Clone, Debug)]
+pub struct UnparsedLine {
+    pub line_number: usize,
+    pub line: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct AppendedLine {
+    pub line_number: usize,
+    pub line: String,
+    pub question_number: u32,
+    pub option_letter: Option<char>,
+}
+
+#[derive(Clone, Debug)]
+pub struct TranscriptQuestion {
+    pub number: u32,
+    pub text: String,
+    pub options: Vec<String>,
+}
+
+#[derive(Default, Clone, Debug)]
+pub struct Transcript {
+    pub questions: Vec<TranscriptQuestion>,
+    pub unparsed_lines: Vec<UnparsedLine>,
+    pub appended_lines: Vec<AppendedLine>,
+}
+
+// Case and delimiter of a question's first option marker, which its later markers must match.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+struct OptionStyle {
+    uppercase: bool,
+    delimiter: char,
+}
+
+impl Transcript {
+    pub fn parse(text: &str) -> Self {
+        let mut transcript = Self::default();
+        let mut continuing = false;
+        let mut option_style = None;
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() {
+                continuing = false;
+
+                continue;
+            }
+
+            if let Some((number, text)) = question_marker(line) {
+                transcript.questions.push(TranscriptQuestion {
+                    number,
+                    text: text.to_owned(),
+                    options: vec![],
+                });
+                continuing = true;
+                option_style = None;
+
+                continue;
+            }
+
+            if let Some(question) = transcript.questions.last_mut() {
+                match option_marker(line) {
+                    Some((letter, style, text))
+                        if letter == option_letter(question.options.len())
+                            && option_style.map_or(true, |option_style| option_style == style) =>
+                    {
+                        question.options.push(text.to_owned());
+                        continuing = true;
+                        option_style = Some(style);
+
+                        continue;
+                    }
+                    _ if continuing => {
+                        let option_letter =
+                            question.options.len().checked_sub(1).map(option_letter);
+                        let text = question.options.last_mut().unwrap_or(&mut question.text);
+                        text.push(' ');
+                        text.push_str(line);
+
+                        transcript.appended_lines.push(AppendedLine {
+                            line_number: index + 1,
+                            line: line.to_owned(),
+                            question_number: question.number,
+                            option_letter,
+                        });
+
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+
+            transcript.unparsed_lines.push(UnparsedLine {
+                line_number: index + 1,
+                line: line.to_owned(),
+            });
+        }
+
+        transcript
+    }
+
+    pub fn into_raw_questions(
+        self,
+        answer_key: &AnswerKey,
+        occurrence: QuestionOccurrenceData,
+    ) -> Result<Vec<RawQuestionData>> {
+        let mut questions = vec![];
+        let mut errors = vec![];
+
+        for question in self.questions {
+            let answers = match answer_key.answers.get(&question.number) {
+                Some(answers) => answers,
+                None => {
+                    errors.push(format!("question {} has no answer", question.number));
+
+                    continue;
+                }
+            };
+
+            if let Some(letter) = answers
+                .iter()
+                .find(|letter| option_index(**letter) >= question.options.len())
+            {
+                errors.push(format!(
+                    "question {} has no option `{letter}`",
+                    question.number
+                ));
+
+                continue;
+            }
+
+            let options = question
+                .options
+                .into_iter()
+                .enumerate()
+                .map(|(index, text)| RawQuestionOptionData {
+                    id: None,
+                    text,
+                    correct: if answers.contains(&option_letter(index)) {
+                        Some(true)
+                    } else {
+                        None
+                    },
+                    explanation: None,
+                })
+                .collect();
+
+            questions.push(RawQuestionData {
+                id: None,
+                evaluation: occurrence.evaluation.clone(),
+                source: occurrence.source,
+                asked_at: occurrence.asked_at,
+                occurrences: vec![],
+                kind: if answers.len() > 1 {
+                    QuestionKind::MultipleChoice
+                } else {
+                    QuestionKind::SingleChoice
+                },
+                text: question.text,
+                image: None,
+                options,
+            });
+        }
+
+        if !errors.is_empty() {
+            bail!("{}", errors.join("\n"));
+        }
+
+        Ok(questions)
+    }
+}
+
+#[derive(Default, Clone, Debug)]
+pub struct AnswerKey {
+    pub answers: BTreeMap<u32, Vec<char>>,
+    pub unparsed_lines: Vec<UnparsedLine>,
+}
+
+impl AnswerKey {
+    pub fn parse(text: &str) -> Self {
+        let mut answer_key = Self::default();
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            match answer_marker(line) {
+                Some((number, letters)) => {
+                    answer_key.answers.insert(number, letters);
+                }
+                None => answer_key.unparsed_lines.push(UnparsedLine {
+                    line_number: index + 1,
+                    line: line.to_owned(),
+                }),
+            }
+        }
+
+        answer_key
+    }
+}
+
+fn question_marker(line: &str) -> Option<(u32, &str)> {
+    let digits_end = line
+        .find(|character: char| !character.is_ascii_digit())
+        .unwrap_or(line.len());
+    let number = line[..digits_end].parse().ok()?;
+
+    let rest = line[digits_end..].strip_prefix([')', '.', '-'])?;
+
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    Some((number, rest.trim()))
+}
+
+fn option_marker(line: &str) -> Option<(char, OptionStyle, &str)> {
+    let mut characters = line.chars();
+    let letter = characters.next()?;
+
+    if !letter.is_ascii_alphabetic() {
+        return None;
+    }
+
+    let rest = characters.as_str();
+    let delimiter = rest
+        .chars()
+        .next()
+        .filter(|delimiter| matches!(delimiter, ')' | '.'))?;
+    let rest = &rest[delimiter.len_utf8()..];
+
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    let style = OptionStyle {
+        uppercase: letter.is_ascii_uppercase(),
+        delimiter,
+    };
+
+    Some((letter.to_ascii_lowercase(), style, rest.trim()))
+}
+
+fn answer_marker(line: &str) -> Option<(u32, Vec<char>)> {
+    let digits_end = line
+        .find(|character: char| !character.is_ascii_digit())
+        .unwrap_or(line.len());
+    let number = line[..digits_end].parse().ok()?;
+
+    let mut letters = vec![];
+
+    for character in line[digits_end..].chars() {
+        match character {
+            character if character.is_ascii_alphabetic() => {
+                letters.push(character.to_ascii_lowercase())
+            }
+            ')' | '.' | '-' | ':' | ',' | '/' => {}
+            character if character.is_whitespace() => {}
+            _ => return None,
+        }
+    }
+
+    if letters.is_empty() {
+        return None;
+    }
+
+    Some((number, letters))
+}
+
+fn option_letter(index: usize) -> char {
+    (b'a' + index as u8) as char
+}
+
+fn option_index(letter: char) -> usize {
+    (letter as u8 - b'a') as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::QuestionSource;
+
+    const TRANSCRIPT: &str = "Parcial 1 - Hematología
+
+1) ¿Cuál es la causa más frecuente
+de anemia?
+a) Ferropenia
+b) Déficit de B12
+c) Talasemia
+
+2. ¿Qué agentes causan diarrea?
+A. Rotavirus
+B. Norovirus
+";
+
+    fn occurrence() -> QuestionOccurrenceData {
+        QuestionOccurrenceData {
+            asked_at: None,
+            evaluation: "parcial_1".to_owned(),
+            source: QuestionSource::Partial,
+        }
+    }
+
+    #[test]
+    fn parses_questions_and_options() {
+        let transcript = Transcript::parse(TRANSCRIPT);
+
+        assert_eq!(transcript.questions.len(), 2);
+        assert_eq!(transcript.questions[0].number, 1);
+        assert_eq!(
+            transcript.questions[0].text,
+            "¿Cuál es la causa más frecuente de anemia?"
+        );
+        assert_eq!(
+            transcript.questions[0].options,
+            ["Ferropenia", "Déficit de B12", "Talasemia"]
+        );
+        assert_eq!(transcript.questions[1].options, ["Rotavirus", "Norovirus"]);
+
+        assert_eq!(transcript.unparsed_lines.len(), 1);
+        assert_eq!(transcript.unparsed_lines[0].line_number, 1);
+    }
+
+    #[test]
+    fn reports_lines_appended_to_options() {
+        let transcript = Transcript::parse(
+            "1) ¿Cuál es la leucemia más frecuente en niños?
+a) Leucemia mieloide aguda
+b) Leucemia
+Página 1 de 3
+",
+        );
+
+        assert_eq!(transcript.questions[0].options[1], "Leucemia Página 1 de 3");
+        assert_eq!(transcript.appended_lines.len(), 1);
+
+        let appended_line = &transcript.appended_lines[0];
+        assert_eq!(appended_line.line_number, 4);
+        assert_eq!(appended_line.question_number, 1);
+        assert_eq!(appended_line.option_letter, Some('b'));
+    }
+
+    #[test]
+    fn reports_lines_appended_to_question_text() {
+        let transcript = Transcript::parse("1) ¿Cuál es la causa\nde anemia?\na) Ferropenia\n");
+
+        assert_eq!(transcript.appended_lines.len(), 1);
+        assert_eq!(transcript.appended_lines[0].option_letter, None);
+    }
+
+    #[test]
+    fn option_markers_keep_the_style_of_the_first_option() {
+        let transcript = Transcript::parse(
+            "1) ¿Qué bacteria produce toxina Shiga?
+a) Shigella dysenteriae
+b) Salmonella
+c) Vibrio cholerae
+d) Cepas enterohemorrágicas de
+E. coli
+",
+        );
+
+        assert_eq!(transcript.questions[0].options.len(), 4);
+        assert_eq!(
+            transcript.questions[0].options[3],
+            "Cepas enterohemorrágicas de E. coli"
+        );
+    }
+
+    #[test]
+    fn parses_answer_keys() {
+        let answer_key = AnswerKey::parse("1) a\n2. B, c\n3 - d\nRespuestas\n");
+
+        assert_eq!(answer_key.answers[&1], ['a']);
+        assert_eq!(answer_key.answers[&2], ['b', 'c']);
+        assert_eq!(answer_key.answers[&3], ['d']);
+        assert_eq!(answer_key.unparsed_lines.len(), 1);
+        assert_eq!(answer_key.unparsed_lines[0].line_number, 4);
+    }
+
+    #[test]
+    fn converts_to_raw_questions() {
+        let transcript = Transcript::parse(TRANSCRIPT);
+        let answer_key = AnswerKey::parse("1) a\n2) a, b\n");
+
+        let questions = transcript
+            .into_raw_questions(&answer_key, occurrence())
+            .unwrap();
+
+        assert_eq!(questions[0].kind, QuestionKind::SingleChoice);
+        assert_eq!(questions[0].options[0].correct, Some(true));
+        assert_eq!(questions[0].options[1].correct, None);
+        assert_eq!(questions[1].kind, QuestionKind::MultipleChoice);
+        assert_eq!(questions[1].evaluation, "parcial_1");
+    }
+
+    #[test]
+    fn rejects_answers_without_matching_questions_or_options() {
+        let transcript = Transcript::parse(TRANSCRIPT);
+        let answer_key = AnswerKey::parse("1) e\n");
+
+        let error = transcript
+            .into_raw_questions(&answer_key, occurrence())
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("question 1 has no option `e`"));
+        assert!(error.contains("question 2 has no answer"));
+    }
+}
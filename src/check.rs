@@ -0,0 +1,20 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use medici_data_sync::load_courses_data;
+
+use crate::report::print_load_report;
+
+pub fn check(data_path: PathBuf, images_path: PathBuf) -> Result<()> {
+    let loaded_courses = load_courses_data(data_path, &images_path, &HashSet::new())?;
+    print_load_report(&loaded_courses);
+
+    println!(
+        "{} course file(s) checked",
+        loaded_courses.courses_data.len()
+    );
+
+    Ok(())
+}
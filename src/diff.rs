@@ -0,0 +1,94 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use medici_data_sync::{diff_courses, load_courses_data, ChangeKind, FieldDiff};
+
+use crate::engine::{Engine, EngineOptions};
+use crate::report::print_load_report;
+
+pub async fn diff(
+    data_path: PathBuf,
+    images_path: PathBuf,
+    engine_options: EngineOptions,
+    json: bool,
+) -> Result<()> {
+    let engine = Engine::new(engine_options)?;
+    let snapshot = engine.sync_snapshot().await?;
+
+    let mut loaded_courses = load_courses_data(data_path, &images_path, &snapshot.known_ids())?;
+
+    for course_data in loaded_courses.courses_data.iter_mut() {
+        course_data.format();
+    }
+    let diffs = diff_courses(&loaded_courses.courses_data, snapshot);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&diffs)?);
+
+        return Ok(());
+    }
+
+    print_load_report(&loaded_courses);
+
+    if diffs.is_empty() {
+        println!("No differences");
+
+        return Ok(());
+    }
+
+    for course_diff in diffs {
+        println!(
+            "{} {}",
+            change_symbol(course_diff.change),
+            course_diff.course_key
+        );
+        print_fields(&course_diff.fields, 4);
+
+        for evaluation_diff in course_diff.evaluations {
+            println!(
+                "  {} evaluation {}",
+                change_symbol(evaluation_diff.change),
+                evaluation_diff.key
+            );
+            print_fields(&evaluation_diff.fields, 6);
+        }
+
+        for question_diff in course_diff.questions {
+            println!(
+                "  {} question {}",
+                change_symbol(question_diff.change),
+                question_diff.id
+            );
+            print_fields(&question_diff.fields, 6);
+
+            for question_option_diff in question_diff.options {
+                println!(
+                    "      {} option {}",
+                    change_symbol(question_option_diff.change),
+                    question_option_diff.id
+                );
+                print_fields(&question_option_diff.fields, 8);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_fields(fields: &[FieldDiff], indent: usize) {
+    for field in fields {
+        println!(
+            "{:indent$}{}: {} -> {}",
+            "", field.field, field.engine, field.local
+        );
+    }
+}
+
+fn change_symbol(change: ChangeKind) -> &'static str {
+    match change {
+        ChangeKind::Added => "+",
+        ChangeKind::Removed => "-",
+        ChangeKind::Changed => "~",
+    }
+}
@@ -0,0 +1,60 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+
+use medici_data_sync::{find_duplicates, load_courses_data, DuplicateQuestion};
+
+use crate::report::print_load_report;
+
+pub fn duplicates(
+    data_path: PathBuf,
+    images_path: PathBuf,
+    threshold: f64,
+    json: bool,
+) -> Result<()> {
+    if !(0.0..=1.0).contains(&threshold) {
+        bail!("Threshold must be between 0 and 1");
+    }
+
+    let loaded_courses = load_courses_data(data_path, &images_path, &HashSet::new())?;
+    let candidates = find_duplicates(&loaded_courses.courses_data, threshold);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&candidates)?);
+
+        return Ok(());
+    }
+
+    print_load_report(&loaded_courses);
+
+    if candidates.is_empty() {
+        println!("No duplicate candidates found");
+
+        return Ok(());
+    }
+
+    for candidate in &candidates {
+        println!("{:.2}", candidate.score);
+
+        for question in &candidate.questions {
+            print_question(question);
+        }
+    }
+
+    println!("{} duplicate candidate(s) found", candidates.len());
+
+    Ok(())
+}
+
+fn print_question(question: &DuplicateQuestion) {
+    let asked_at = question
+        .asked_at
+        .map(|asked_at| asked_at.to_string())
+        .unwrap_or_else(|| "-".to_owned());
+
+    println!(
+        "  {} {}/{} {asked_at}: {}",
+        question.id, question.course_key, question.evaluation, question.text
+    );
+}
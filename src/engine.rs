@@ -0,0 +1,151 @@
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use clap::Args;
+use rand::Rng;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use secrecy::{ExposeSecret, Secret};
+use url::Url;
+use uuid::Uuid;
+
+use medici_data_sync::{SyncData, SyncMetadata, SyncSnapshot};
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+#[derive(Args, Clone, Debug)]
+pub struct EngineOptions {
+    #[clap(long, value_parser, value_name = "ENGINE_URL", env = "ENGINE_URL")]
+    engine_url: Url,
+
+    #[clap(long, value_parser, value_name = "ENGINE_KEY", env = "ENGINE_KEY")]
+    engine_key: Secret<String>,
+
+    #[clap(long, value_parser, value_name = "SECONDS", default_value = "10")]
+    connect_timeout: u64,
+
+    #[clap(long, value_parser, value_name = "SECONDS", default_value = "120")]
+    request_timeout: u64,
+
+    /// Number of times a failed engine request is retried before giving up
+    #[clap(long, value_parser, value_name = "COUNT", default_value = "5")]
+    max_retries: u32,
+}
+
+pub struct Engine {
+    client: reqwest::Client,
+    url: Url,
+    max_retries: u32,
+}
+
+impl Engine {
+    pub fn new(options: EngineOptions) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .default_headers(
+                [(
+                    reqwest::header::AUTHORIZATION,
+                    format!("Bearer {}", options.engine_key.expose_secret()).parse()?,
+                )]
+                .into_iter()
+                .collect(),
+            )
+            .connect_timeout(Duration::from_secs(options.connect_timeout))
+            .timeout(Duration::from_secs(options.request_timeout))
+            .build()?;
+
+        Ok(Self {
+            client,
+            url: options.engine_url,
+            max_retries: options.max_retries,
+        })
+    }
+
+    pub async fn sync_metadata(&self) -> Result<SyncMetadata> {
+        let url = self.url.join("sync-metadata")?;
+        let response = self.send(|| self.client.get(url.clone())).await?;
+
+        Ok(response.json().await?)
+    }
+
+    pub async fn sync_data(&self, data: &SyncData, idempotency_key: Uuid) -> Result<()> {
+        let url = self.url.join("sync-data")?;
+
+        self.send(|| {
+            self.client
+                .post(url.clone())
+                .header(IDEMPOTENCY_KEY_HEADER, idempotency_key.to_string())
+                .json(data)
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn sync_snapshot(&self) -> Result<SyncSnapshot> {
+        let url = self.url.join("sync-snapshot")?;
+        let response = self.send(|| self.client.get(url.clone())).await?;
+
+        Ok(response.json().await?)
+    }
+
+    async fn send(&self, request: impl Fn() -> RequestBuilder) -> Result<Response> {
+        let mut retries = 0;
+
+        loop {
+            let result = request().send().await;
+
+            let retryable = match &result {
+                Ok(response) => is_retryable_status(response.status()),
+                Err(error) => error.is_connect() || error.is_timeout(),
+            };
+
+            if !retryable || retries >= self.max_retries {
+                let response = result?;
+
+                if !response.status().is_success() {
+                    bail!("Error {}", response.status());
+                }
+
+                return Ok(response);
+            }
+
+            retries += 1;
+            let delay = backoff_delay(retries);
+
+            match result {
+                Ok(response) => eprintln!(
+                    "Engine responded {}, retrying in {delay:?} ({retries}/{})",
+                    response.status(),
+                    self.max_retries
+                ),
+                Err(error) => eprintln!(
+                    "Engine request failed: {error}, retrying in {delay:?} ({retries}/{})",
+                    self.max_retries
+                ),
+            }
+
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::REQUEST_TIMEOUT
+            | StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+fn backoff_delay(retries: u32) -> Duration {
+    let max_delay = INITIAL_BACKOFF
+        .saturating_mul(2u32.saturating_pow(retries - 1))
+        .min(MAX_BACKOFF);
+
+    max_delay.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+}
@@ -0,0 +1,295 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use clap::Subcommand;
+use genanki_rs::{Deck, Field, Model, Note, Package, Template};
+
+use medici_data_sync::{
+    escape_html, gift, load_courses_data, moodle_xml, write_data, CourseData, QuestionData,
+    QuestionOptionData,
+};
+
+use crate::report::print_load_report;
+
+const ANKI_MODEL_ID: i64 = 1_664_928_000_000;
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum ExportCommand {
+    /// Export a course as an Anki package
+    Anki {
+        /// Key of the course to export
+        #[clap(short, long, value_parser, value_name = "KEY")]
+        course_key: String,
+
+        /// Path of the exported file, defaults to <course key>.apkg
+        #[clap(short, long, value_parser, value_name = "PATH")]
+        output_path: Option<PathBuf>,
+
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "PATH",
+            default_value = "./data"
+        )]
+        data_path: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "PATH",
+            default_value = "./images"
+        )]
+        images_path: PathBuf,
+    },
+    /// Export a course as Moodle XML with embedded images
+    Moodle {
+        /// Key of the course to export
+        #[clap(short, long, value_parser, value_name = "KEY")]
+        course_key: String,
+
+        /// Path of the exported file, defaults to <course key>.xml
+        #[clap(short, long, value_parser, value_name = "PATH")]
+        output_path: Option<PathBuf>,
+
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "PATH",
+            default_value = "./data"
+        )]
+        data_path: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "PATH",
+            default_value = "./images"
+        )]
+        images_path: PathBuf,
+    },
+    /// Export a course in the GIFT format, without images
+    Gift {
+        /// Key of the course to export
+        #[clap(short, long, value_parser, value_name = "KEY")]
+        course_key: String,
+
+        /// Path of the exported file, defaults to <course key>.gift
+        #[clap(short, long, value_parser, value_name = "PATH")]
+        output_path: Option<PathBuf>,
+
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "PATH",
+            default_value = "./data"
+        )]
+        data_path: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "PATH",
+            default_value = "./images"
+        )]
+        images_path: PathBuf,
+    },
+}
+
+pub fn export(command: ExportCommand) -> Result<()> {
+    match command {
+        ExportCommand::Anki {
+            course_key,
+            output_path,
+            data_path,
+            images_path,
+        } => {
+            let course_data = load_course_data(data_path, &images_path, &course_key)?;
+            let output_path =
+                output_path.unwrap_or_else(|| default_output_path(&course_key, "apkg"));
+
+            export_anki(&course_data, &images_path, &output_path)?;
+
+            print_exported(&course_data, &output_path);
+        }
+        ExportCommand::Moodle {
+            course_key,
+            output_path,
+            data_path,
+            images_path,
+        } => {
+            let course_data = load_course_data(data_path, &images_path, &course_key)?;
+            let output_path =
+                output_path.unwrap_or_else(|| default_output_path(&course_key, "xml"));
+
+            write_data(output_path.clone(), moodle_xml(&course_data, &images_path)?)?;
+
+            print_exported(&course_data, &output_path);
+        }
+        ExportCommand::Gift {
+            course_key,
+            output_path,
+            data_path,
+            images_path,
+        } => {
+            let course_data = load_course_data(data_path, &images_path, &course_key)?;
+            let output_path =
+                output_path.unwrap_or_else(|| default_output_path(&course_key, "gift"));
+
+            write_data(output_path.clone(), gift(&course_data)?)?;
+
+            print_exported(&course_data, &output_path);
+
+            let image_count = course_data
+                .questions
+                .iter()
+                .filter(|question| question.image_file_name.is_some())
+                .count();
+
+            if image_count > 0 {
+                eprintln!(
+                    "{image_count} image(s) not included, GIFT does not support embedded images"
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn default_output_path(course_key: &str, extension: &str) -> PathBuf {
+    PathBuf::from(format!("{course_key}.{extension}"))
+}
+
+fn print_exported(course_data: &CourseData, output_path: &Path) {
+    println!(
+        "{} question(s) exported to {}",
+        course_data.questions.len(),
+        output_path.display()
+    );
+}
+
+fn load_course_data(
+    data_path: PathBuf,
+    images_path: &Path,
+    course_key: &str,
+) -> Result<CourseData> {
+    let loaded_courses = load_courses_data(data_path, images_path, &HashSet::new())?;
+    print_load_report(&loaded_courses);
+
+    loaded_courses
+        .courses_data
+        .into_iter()
+        .find(|course_data| course_data.key == course_key)
+        .ok_or_else(|| anyhow!("Course {course_key} not found"))
+}
+
+fn export_anki(course_data: &CourseData, images_path: &Path, output_path: &Path) -> Result<()> {
+    let model = anki_model();
+    let mut deck = Deck::new(
+        anki_deck_id(&course_data.key),
+        &course_data.name,
+        &course_data.short_name,
+    );
+    let mut media_files = vec![];
+
+    for question in &course_data.questions {
+        let image = match question.image_path(images_path) {
+            Some(image_path) => {
+                media_files.push(image_path.to_string_lossy().into_owned());
+
+                format!(
+                    "<img src=\"{}\">",
+                    escape_html(&question.image_file_name.as_ref().unwrap().to_string_lossy())
+                )
+            }
+            None => String::new(),
+        };
+
+        let options = anki_options(question);
+        let answer = options
+            .iter()
+            .filter(|(_, question_option)| question_option.correct)
+            .map(|(letter, _)| letter.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let explanation = options
+            .iter()
+            .filter_map(|(letter, question_option)| {
+                Some(format!(
+                    "<p><b>{letter}.</b> {}</p>",
+                    escape_html(question_option.explanation.as_ref()?)
+                ))
+            })
+            .collect::<String>();
+        let options = options
+            .iter()
+            .map(|(letter, question_option)| {
+                format!(
+                    "<p><b>{letter}.</b> {}</p>",
+                    escape_html(&question_option.text)
+                )
+            })
+            .collect::<String>();
+
+        let tag = format!("{}::{}", course_data.key, question.evaluation);
+        let note = Note::new(
+            model.clone(),
+            vec![
+                &escape_html(&question.text),
+                &image,
+                &options,
+                &answer,
+                &explanation,
+            ],
+        )?
+        .guid(question.id)
+        .tags([tag]);
+
+        deck.add_note(note);
+    }
+
+    let media_files = media_files.iter().map(String::as_str).collect();
+    let mut package = Package::new(vec![deck], media_files)?;
+    package.write_to_file(&output_path.to_string_lossy())?;
+
+    Ok(())
+}
+
+fn anki_model() -> Model {
+    Model::new(
+        ANKI_MODEL_ID,
+        "Medici question",
+        vec![
+            Field::new("Question"),
+            Field::new("Image"),
+            Field::new("Options"),
+            Field::new("Answer"),
+            Field::new("Explanation"),
+        ],
+        vec![Template::new("Card 1")
+            .qfmt("<p>{{Question}}</p>{{Image}}{{Options}}")
+            .afmt("{{FrontSide}}<hr id=\"answer\"><p><b>{{Answer}}</b></p>{{Explanation}}")],
+    )
+}
+
+fn anki_deck_id(course_key: &str) -> i64 {
+    let hash = blake3::hash(course_key.as_bytes());
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&hash.as_bytes()[..8]);
+
+    (u64::from_be_bytes(bytes) >> 1) as i64
+}
+
+fn anki_options(question: &QuestionData) -> Vec<(char, &QuestionOptionData)> {
+    let mut question_options: Vec<&QuestionOptionData> = question.question_options.iter().collect();
+    question_options.sort_by_key(|question_option| question_option.id);
+
+    ('A'..='Z').zip(question_options).collect()
+}
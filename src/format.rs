@@ -1,15 +1,34 @@
+use std::collections::HashSet;
 use std::path::PathBuf;
 
 use anyhow::Result;
 
-use medici_data_sync::{read_data_dir, CourseData};
+use medici_data_sync::{load_courses_data_and_write_formatted, write_data, CourseData, MergedIds};
 
-pub async fn format(data_path: PathBuf, images_path: PathBuf) -> Result<()> {
-    let entries = read_data_dir(data_path)?;
+use crate::report::print_load_report;
 
-    for dir_entry in entries {
-        CourseData::load_and_write_formatted(dir_entry?, images_path.clone()).await?;
+pub async fn format(
+    data_path: PathBuf,
+    images_path: PathBuf,
+    merged_ids_path: Option<PathBuf>,
+) -> Result<()> {
+    let loaded_courses =
+        load_courses_data_and_write_formatted(data_path, images_path, &HashSet::new()).await?;
+    print_load_report(&loaded_courses);
+
+    if let Some(merged_ids_path) = merged_ids_path {
+        write_merged_ids(merged_ids_path, &loaded_courses.courses_data)?;
     }
 
     Ok(())
 }
+
+fn write_merged_ids(path: PathBuf, courses_data: &[CourseData]) -> Result<()> {
+    let mut merged_ids = MergedIds::default();
+
+    for course_data in courses_data {
+        merged_ids.extend(&course_data.merged_ids);
+    }
+
+    write_data(path, serde_json::to_string_pretty(&merged_ids)?)
+}
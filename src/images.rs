@@ -0,0 +1,111 @@
+use anyhow::{bail, Result};
+use clap::Args;
+use s3::{creds::Credentials, Bucket, Region};
+
+use medici_data_sync::{ImageData, ImageFormat};
+
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+#[derive(Args, Clone, Debug)]
+pub struct S3Options {
+    #[clap(long, value_parser, value_name = "S3_ENDPOINT", env = "S3_ENDPOINT")]
+    s3_endpoint: Option<String>,
+
+    #[clap(
+        long,
+        value_parser,
+        value_name = "S3_REGION",
+        env = "AWS_REGION",
+        default_value = "sa-east-1"
+    )]
+    s3_region: String,
+}
+
+impl S3Options {
+    pub fn bucket(&self, bucket_name: &str) -> Result<Bucket> {
+        let credentials = Credentials::from_env()?;
+
+        let bucket = match &self.s3_endpoint {
+            Some(endpoint) => {
+                let region = Region::Custom {
+                    region: self.s3_region.clone(),
+                    endpoint: endpoint.trim_end_matches('/').to_owned(),
+                };
+
+                Bucket::new(bucket_name, region, credentials)?.with_path_style()
+            }
+            None => Bucket::new(bucket_name, self.s3_region.parse()?, credentials)?,
+        };
+
+        Ok(bucket)
+    }
+}
+
+pub async fn upload_images(
+    bucket: &Bucket,
+    images: &[ImageData],
+    unchanged_keys: &[String],
+) -> Result<()> {
+    for key in unchanged_keys {
+        println!("Skipped {key} (unchanged)");
+    }
+
+    let mut failed_keys = vec![];
+
+    for image_data in images {
+        match upload_image(bucket, image_data).await {
+            Ok(()) => println!("Uploaded {}", image_data.key),
+            Err(error) => {
+                eprintln!("Failed to upload {}: {error:#}", image_data.key);
+                failed_keys.push(image_data.key.as_str());
+            }
+        }
+    }
+
+    if !failed_keys.is_empty() {
+        bail!(
+            "{} image(s) could not be uploaded: {}",
+            failed_keys.len(),
+            failed_keys.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn delete_images(bucket: &Bucket, keys: &[String]) -> Result<()> {
+    let mut failed_keys = vec![];
+
+    for key in keys {
+        match bucket.delete_object(key).await {
+            Ok(_) => println!("Deleted {key}"),
+            Err(error) => {
+                eprintln!("Failed to delete {key}: {error:#}");
+                failed_keys.push(key.as_str());
+            }
+        }
+    }
+
+    if !failed_keys.is_empty() {
+        bail!(
+            "{} image(s) could not be deleted: {}",
+            failed_keys.len(),
+            failed_keys.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+async fn upload_image(bucket: &Bucket, image_data: &ImageData) -> Result<()> {
+    let content = tokio::fs::read(&image_data.path).await?;
+    let content_type = ImageFormat::from_path(&image_data.path)
+        .map(|image_format| image_format.content_type())
+        .unwrap_or(DEFAULT_CONTENT_TYPE);
+
+    bucket
+        .put_object_with_content_type(&image_data.key, &content, content_type)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,379 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use clap::Subcommand;
+use uuid::Uuid;
+
+use medici_data_sync::{
+    load_courses_data_and_write_formatted, read_csv_questions, write_data, AnswerKey, AppendedLine,
+    Diagnostics, ImportedImage, MoodleImport, QuestionOccurrenceData, QuestionSource,
+    RawCourseData, RawQuestionData, Transcript, UnparsedLine,
+};
+
+use crate::report::print_load_report;
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum ImportCommand {
+    /// Import questions from a CSV file, or a TSV file if its extension is .tsv
+    Csv {
+        #[clap(value_parser, value_name = "PATH")]
+        input_path: PathBuf,
+
+        /// Key of the course the questions are imported into
+        #[clap(short, long, value_parser, value_name = "KEY")]
+        course_key: String,
+
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "PATH",
+            default_value = "./data"
+        )]
+        data_path: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "PATH",
+            default_value = "./images"
+        )]
+        images_path: PathBuf,
+    },
+    /// Import numbered questions with lettered options from a plain-text exam transcript
+    Transcript {
+        #[clap(value_parser, value_name = "PATH")]
+        input_path: PathBuf,
+
+        /// File with one "<question number>) <correct letters>" line per question
+        #[clap(short, long, value_parser, value_name = "PATH")]
+        answer_key_path: PathBuf,
+
+        /// Key of the course the questions are imported into
+        #[clap(short, long, value_parser, value_name = "KEY")]
+        course_key: String,
+
+        /// Evaluation the questions were asked in
+        #[clap(short, long, value_parser, value_name = "KEY")]
+        evaluation: String,
+
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "SOURCE",
+            default_value = "partial"
+        )]
+        source: QuestionSource,
+
+        /// Date the exam was taken, as YYYY-MM-DD
+        #[clap(long, value_parser, value_name = "DATE")]
+        asked_at: Option<NaiveDate>,
+
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "PATH",
+            default_value = "./data"
+        )]
+        data_path: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "PATH",
+            default_value = "./images"
+        )]
+        images_path: PathBuf,
+    },
+    /// Import multichoice questions from a Moodle XML export
+    Moodle {
+        #[clap(value_parser, value_name = "PATH")]
+        input_path: PathBuf,
+
+        /// Key of the course the questions are imported into
+        #[clap(short, long, value_parser, value_name = "KEY")]
+        course_key: String,
+
+        /// Evaluation of questions whose category does not match an evaluation of the course
+        #[clap(short, long, value_parser, value_name = "KEY")]
+        evaluation: String,
+
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "SOURCE",
+            default_value = "partial"
+        )]
+        source: QuestionSource,
+
+        /// Date the exam was taken, as YYYY-MM-DD
+        #[clap(long, value_parser, value_name = "DATE")]
+        asked_at: Option<NaiveDate>,
+
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "PATH",
+            default_value = "./data"
+        )]
+        data_path: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "PATH",
+            default_value = "./images"
+        )]
+        images_path: PathBuf,
+    },
+}
+
+pub async fn import(command: ImportCommand) -> Result<()> {
+    match command {
+        ImportCommand::Csv {
+            input_path,
+            course_key,
+            data_path,
+            images_path,
+        } => {
+            let questions = read_csv_questions(&input_path)?;
+            // Row 1 is the header.
+            let origins = (0..questions.len())
+                .map(|index| format!("row {} of {}", index + 2, input_path.display()))
+                .collect();
+
+            import_questions(
+                data_path,
+                images_path,
+                &course_key,
+                questions,
+                origins,
+                vec![],
+            )
+            .await
+        }
+        ImportCommand::Transcript {
+            input_path,
+            answer_key_path,
+            course_key,
+            evaluation,
+            source,
+            asked_at,
+            data_path,
+            images_path,
+        } => {
+            let transcript = Transcript::parse(&read_text(&input_path)?);
+            let answer_key = AnswerKey::parse(&read_text(&answer_key_path)?);
+
+            print_unparsed_lines(&input_path, &transcript.unparsed_lines);
+            print_appended_lines(&input_path, &transcript.appended_lines);
+            print_unparsed_lines(&answer_key_path, &answer_key.unparsed_lines);
+
+            let occurrence = QuestionOccurrenceData {
+                asked_at,
+                evaluation,
+                source,
+            };
+            let origins = transcript
+                .questions
+                .iter()
+                .map(|question| format!("question {} of {}", question.number, input_path.display()))
+                .collect();
+            let questions = transcript.into_raw_questions(&answer_key, occurrence)?;
+
+            import_questions(
+                data_path,
+                images_path,
+                &course_key,
+                questions,
+                origins,
+                vec![],
+            )
+            .await
+        }
+        ImportCommand::Moodle {
+            input_path,
+            course_key,
+            evaluation,
+            source,
+            asked_at,
+            data_path,
+            images_path,
+        } => {
+            let raw_course_data = read_raw_course_data(&course_path(&data_path, &course_key))?;
+            let occurrence = QuestionOccurrenceData {
+                asked_at,
+                evaluation,
+                source,
+            };
+            let moodle_import = MoodleImport::parse(
+                &read_text(&input_path)?,
+                &raw_course_data.evaluations,
+                occurrence,
+            )?;
+
+            for skipped_question in &moodle_import.skipped_questions {
+                eprintln!(
+                    "{}: skipped question `{}`: {}",
+                    input_path.display(),
+                    skipped_question.name,
+                    skipped_question.reason
+                );
+            }
+
+            let origins = moodle_import
+                .question_names
+                .iter()
+                .map(|name| format!("`{name}` in {}", input_path.display()))
+                .collect();
+
+            import_questions(
+                data_path,
+                images_path,
+                &course_key,
+                moodle_import.questions,
+                origins,
+                moodle_import.images,
+            )
+            .await
+        }
+    }
+}
+
+fn course_path(data_path: &Path, course_key: &str) -> PathBuf {
+    let mut course_path = data_path.to_owned();
+    course_path.push(format!("{course_key}.json"));
+
+    course_path
+}
+
+fn read_raw_course_data(course_path: &Path) -> Result<RawCourseData> {
+    let data = fs::read(course_path)
+        .with_context(|| format!("Could not read course file {}", course_path.display()))?;
+
+    RawCourseData::from_slice(&data)
+}
+
+fn read_text(path: &Path) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!("Could not read {}", path.display()))
+}
+
+fn print_unparsed_lines(path: &Path, unparsed_lines: &[UnparsedLine]) {
+    for unparsed_line in unparsed_lines {
+        eprintln!(
+            "{}:{}: could not parse `{}`",
+            path.display(),
+            unparsed_line.line_number,
+            unparsed_line.line
+        );
+    }
+}
+
+fn print_appended_lines(path: &Path, appended_lines: &[AppendedLine]) {
+    for appended_line in appended_lines {
+        let target = match appended_line.option_letter {
+            Some(option_letter) => format!(
+                "option {option_letter}) of question {}",
+                appended_line.question_number
+            ),
+            None => format!("question {}", appended_line.question_number),
+        };
+
+        eprintln!(
+            "{}:{}: appended `{}` to {target}",
+            path.display(),
+            appended_line.line_number,
+            appended_line.line
+        );
+    }
+}
+
+async fn import_questions(
+    data_path: PathBuf,
+    images_path: PathBuf,
+    course_key: &str,
+    questions: Vec<RawQuestionData>,
+    origins: Vec<String>,
+    images: Vec<ImportedImage>,
+) -> Result<()> {
+    let course_path = course_path(&data_path, course_key);
+
+    let original_data = fs::read(&course_path)
+        .with_context(|| format!("Could not read course file {}", course_path.display()))?;
+    let mut raw_course_data = RawCourseData::from_slice(&original_data)?;
+
+    // Existing ids survive when an imported question duplicates one already in the course.
+    let existing_ids: HashSet<Uuid> = raw_course_data
+        .questions
+        .iter()
+        .flat_map(|question| {
+            question
+                .options
+                .iter()
+                .map(|question_option| question_option.id)
+                .chain([question.id])
+        })
+        .flatten()
+        .collect();
+
+    let first_index = raw_course_data.questions.len();
+    let question_count = questions.len();
+    raw_course_data.questions.extend(questions);
+
+    let mut course_images_path = images_path.clone();
+    course_images_path.push(course_key);
+    fs::create_dir_all(&course_images_path)?;
+
+    let mut image_paths = vec![];
+
+    for image in images {
+        let mut image_path = course_images_path.clone();
+        image_path.push(image.file_name);
+
+        fs::write(&image_path, image.content)?;
+        image_paths.push(image_path);
+    }
+
+    write_data(
+        course_path.clone(),
+        serde_json::to_string_pretty(&raw_course_data)?,
+    )?;
+
+    match load_courses_data_and_write_formatted(data_path, images_path, &existing_ids).await {
+        Ok(loaded_courses) => print_load_report(&loaded_courses),
+        Err(error) => {
+            fs::write(&course_path, original_data)?;
+
+            for image_path in image_paths {
+                fs::remove_file(image_path)?;
+            }
+
+            return Err(match error.downcast::<Diagnostics>() {
+                Ok(mut diagnostics) => {
+                    diagnostics.set_question_origins(
+                        &fs::canonicalize(&course_path)?,
+                        first_index,
+                        &origins,
+                    );
+
+                    diagnostics.into()
+                }
+                Err(error) => error,
+            });
+        }
+    }
+
+    println!("{question_count} question(s) imported into {course_key}");
+
+    Ok(())
+}
@@ -1,11 +1,27 @@
+use std::net::SocketAddr;
 use std::path::PathBuf;
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use secrecy::Secret;
-use url::Url;
 
+use engine::EngineOptions;
+use export::ExportCommand;
+use images::S3Options;
+use import::ImportCommand;
+use sync::BatchOptions;
+
+mod check;
+mod diff;
+mod duplicates;
+mod engine;
+mod export;
 mod format;
+mod images;
+mod import;
+mod mock_engine;
+mod report;
+mod schema;
 mod sync;
 
 #[derive(Parser, Clone, Debug)]
@@ -26,16 +42,71 @@ impl Synchronizer {
             Command::Sync {
                 data_path,
                 images_path,
-                engine_url,
-                engine_key,
+                engine_options,
+                s3_options,
+                batch_options,
+                dry_run,
+                json,
             } => {
-                sync::sync(data_path, images_path, engine_url, engine_key).await?;
+                sync::sync(
+                    data_path,
+                    images_path,
+                    engine_options,
+                    s3_options,
+                    batch_options,
+                    dry_run,
+                    json,
+                )
+                .await?;
             }
             Command::Format {
                 data_path,
                 images_path,
+                merged_ids_path,
+            } => {
+                format::format(data_path, images_path, merged_ids_path).await?;
+            }
+            Command::Check {
+                data_path,
+                images_path,
+            } => {
+                check::check(data_path, images_path)?;
+            }
+            Command::Diff {
+                data_path,
+                images_path,
+                engine_options,
+                json,
+            } => {
+                diff::diff(data_path, images_path, engine_options, json).await?;
+            }
+            Command::Duplicates {
+                data_path,
+                images_path,
+                threshold,
+                json,
+            } => {
+                duplicates::duplicates(data_path, images_path, threshold, json)?;
+            }
+            Command::Export { command } => {
+                export::export(command)?;
+            }
+            Command::Import { command } => {
+                import::import(command).await?;
+            }
+            Command::Schema {
+                data_path,
+                output_path,
             } => {
-                format::format(data_path, images_path).await?;
+                schema::schema(data_path, output_path)?;
+            }
+            Command::MockEngine {
+                state_path,
+                address,
+                engine_key,
+                fail_after,
+            } => {
+                mock_engine::mock_engine(state_path, address, engine_key, fail_after).await?;
             }
         }
 
@@ -63,6 +134,10 @@ enum Command {
             default_value = "./images"
         )]
         images_path: PathBuf,
+
+        /// Write the removed id -> kept id mapping of merged duplicates to this file
+        #[clap(long, value_parser, value_name = "PATH")]
+        merged_ids_path: Option<PathBuf>,
     },
     Sync {
         #[clap(
@@ -83,11 +158,147 @@ enum Command {
         )]
         images_path: PathBuf,
 
-        #[clap(long, value_parser, value_name = "ENGINE_URL", env = "ENGINE_URL")]
-        engine_url: Url,
+        #[clap(flatten)]
+        engine_options: EngineOptions,
+
+        #[clap(flatten)]
+        s3_options: S3Options,
+
+        #[clap(flatten)]
+        batch_options: BatchOptions,
+
+        /// Print the computed sync plan without syncing data or images
+        #[clap(long, value_parser)]
+        dry_run: bool,
+
+        /// Print the full sync plan as JSON (requires --dry-run)
+        #[clap(long, value_parser, requires = "dry-run")]
+        json: bool,
+    },
+    Check {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "PATH",
+            default_value = "./data"
+        )]
+        data_path: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "PATH",
+            default_value = "./images"
+        )]
+        images_path: PathBuf,
+    },
+    Diff {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "PATH",
+            default_value = "./data"
+        )]
+        data_path: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "PATH",
+            default_value = "./images"
+        )]
+        images_path: PathBuf,
+
+        #[clap(flatten)]
+        engine_options: EngineOptions,
+
+        /// Print the differences as JSON
+        #[clap(long, value_parser)]
+        json: bool,
+    },
+    Duplicates {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "PATH",
+            default_value = "./data"
+        )]
+        data_path: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "PATH",
+            default_value = "./images"
+        )]
+        images_path: PathBuf,
+
+        /// Minimum similarity, between 0 and 1, for a pair to be reported
+        #[clap(short, long, value_parser, default_value_t = 0.85)]
+        threshold: f64,
+
+        /// Print the candidate pairs as JSON
+        #[clap(long, value_parser)]
+        json: bool,
+    },
+    Export {
+        #[clap(subcommand)]
+        command: ExportCommand,
+    },
+    Import {
+        #[clap(subcommand)]
+        command: ImportCommand,
+    },
+    Schema {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "PATH",
+            default_value = "./data"
+        )]
+        data_path: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "PATH",
+            default_value = "./.vscode/course-data.schema.json"
+        )]
+        output_path: PathBuf,
+    },
+    MockEngine {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "PATH",
+            default_value = "./mock-engine.json"
+        )]
+        state_path: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_name = "ADDRESS",
+            default_value = "127.0.0.1:8080"
+        )]
+        address: SocketAddr,
 
         #[clap(long, value_parser, value_name = "ENGINE_KEY", env = "ENGINE_KEY")]
-        engine_key: Secret<String>,
+        engine_key: Option<Secret<String>>,
+
+        /// Reject sync-data requests after accepting COUNT of them, to exercise --resume
+        #[clap(long, value_parser, value_name = "COUNT")]
+        fail_after: Option<usize>,
     },
 }
 
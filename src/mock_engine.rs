@@ -0,0 +1,250 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use anyhow::Result;
+use axum::{
+    extract::Extension,
+    http::{header, HeaderMap, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use medici_data_sync::{
+    write_data, CourseData, CourseEvaluationData, QuestionData, QuestionOptionData, SyncData,
+    SyncMetadata, SyncSnapshot,
+};
+
+use crate::engine::IDEMPOTENCY_KEY_HEADER;
+
+const DEFAULT_IMAGES_BUCKET_NAME: &str = "images";
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+struct MockEngineState {
+    images_bucket_name: String,
+    courses: BTreeMap<String, CourseData>,
+    course_evaluations: BTreeMap<String, CourseEvaluationData>,
+    questions: BTreeMap<Uuid, QuestionData>,
+    question_options: BTreeMap<Uuid, QuestionOptionData>,
+    images: BTreeMap<String, String>,
+    processed_batches: BTreeSet<Uuid>,
+}
+
+impl MockEngineState {
+    fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self {
+                images_bucket_name: DEFAULT_IMAGES_BUCKET_NAME.to_owned(),
+                ..Default::default()
+            });
+        }
+
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    fn write(&self, path: &Path) -> Result<()> {
+        write_data(path.to_owned(), serde_json::to_string_pretty(self)?)
+    }
+
+    fn sync_metadata(&self) -> SyncMetadata {
+        SyncMetadata {
+            courses_metadata: self
+                .courses
+                .iter()
+                .map(|(key, course)| (key.clone(), course.hash.clone()))
+                .collect(),
+            questions_metadata: self
+                .questions
+                .iter()
+                .map(|(id, question)| (*id, question.hash.clone()))
+                .collect(),
+            question_options_metadata: self
+                .question_options
+                .iter()
+                .map(|(id, question_option)| (*id, question_option.hash.clone()))
+                .collect(),
+            course_evaluations_metadata: self
+                .course_evaluations
+                .iter()
+                .map(|(key, evaluation)| (key.clone(), evaluation.hash.clone()))
+                .collect(),
+            images_metadata: self
+                .images
+                .iter()
+                .map(|(key, hash)| (key.clone(), hash.clone()))
+                .collect(),
+            images_bucket_name: self.images_bucket_name.clone(),
+        }
+    }
+
+    fn sync_snapshot(&self) -> SyncSnapshot {
+        SyncSnapshot {
+            courses: self.courses.values().cloned().collect(),
+            questions: self.questions.values().cloned().collect(),
+            question_options: self.question_options.values().cloned().collect(),
+            course_evaluations: self.course_evaluations.values().cloned().collect(),
+        }
+    }
+
+    fn apply(&mut self, data: SyncData) {
+        for course in data.courses_to_sync {
+            self.courses.insert(course.key.clone(), course);
+        }
+
+        for evaluation in data.course_evaluations_to_sync {
+            let full_key = CourseEvaluationData::full_key(
+                evaluation.course_key.as_deref().unwrap_or_default(),
+                &evaluation.key,
+            );
+
+            self.course_evaluations.insert(full_key, evaluation);
+        }
+
+        for question in data.questions_to_sync {
+            self.questions.insert(question.id, question);
+        }
+
+        for question_option in data.question_options_to_sync {
+            self.question_options
+                .insert(question_option.id, question_option);
+        }
+
+        for image in data.images_to_sync {
+            self.images.insert(image.key, image.hash);
+        }
+
+        for key in data.images_to_delete {
+            self.images.remove(&key);
+        }
+
+        for id in data.question_options_to_delete {
+            self.question_options.remove(&id);
+        }
+
+        for id in data.questions_to_delete {
+            self.questions.remove(&id);
+        }
+
+        for key in data.course_evaluations_to_delete {
+            self.course_evaluations.remove(&key);
+        }
+
+        for key in data.courses_to_delete {
+            self.courses.remove(&key);
+        }
+    }
+}
+
+struct MockEngine {
+    state: Mutex<MockEngineState>,
+    state_path: PathBuf,
+    engine_key: Option<Secret<String>>,
+    fail_after: Option<usize>,
+    accepted_batches: AtomicUsize,
+}
+
+impl MockEngine {
+    fn authorize(&self, headers: &HeaderMap) -> Result<(), StatusCode> {
+        let engine_key = match &self.engine_key {
+            Some(engine_key) => engine_key,
+            None => return Ok(()),
+        };
+
+        let expected = format!("Bearer {}", engine_key.expose_secret());
+
+        match headers.get(header::AUTHORIZATION) {
+            Some(value) if value.as_bytes() == expected.as_bytes() => Ok(()),
+            _ => Err(StatusCode::UNAUTHORIZED),
+        }
+    }
+}
+
+pub async fn mock_engine(
+    state_path: PathBuf,
+    address: SocketAddr,
+    engine_key: Option<Secret<String>>,
+    fail_after: Option<usize>,
+) -> Result<()> {
+    let engine = Arc::new(MockEngine {
+        state: Mutex::new(MockEngineState::load(&state_path)?),
+        state_path,
+        engine_key,
+        fail_after,
+        accepted_batches: AtomicUsize::new(0),
+    });
+
+    let app = Router::new()
+        .route("/sync-metadata", get(sync_metadata))
+        .route("/sync-data", post(sync_data))
+        .route("/sync-snapshot", get(sync_snapshot))
+        .layer(Extension(engine));
+
+    let server = axum::Server::try_bind(&address)?.serve(app.into_make_service());
+
+    println!("Mock engine listening on http://{}/", server.local_addr());
+
+    server.await?;
+
+    Ok(())
+}
+
+async fn sync_metadata(
+    Extension(engine): Extension<Arc<MockEngine>>,
+    headers: HeaderMap,
+) -> Result<Json<SyncMetadata>, StatusCode> {
+    engine.authorize(&headers)?;
+
+    Ok(Json(engine.state.lock().await.sync_metadata()))
+}
+
+async fn sync_snapshot(
+    Extension(engine): Extension<Arc<MockEngine>>,
+    headers: HeaderMap,
+) -> Result<Json<SyncSnapshot>, StatusCode> {
+    engine.authorize(&headers)?;
+
+    Ok(Json(engine.state.lock().await.sync_snapshot()))
+}
+
+async fn sync_data(
+    Extension(engine): Extension<Arc<MockEngine>>,
+    headers: HeaderMap,
+    Json(data): Json<SyncData>,
+) -> Result<StatusCode, StatusCode> {
+    engine.authorize(&headers)?;
+
+    let idempotency_key = headers
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| Uuid::parse_str(value).ok());
+
+    let mut state = engine.state.lock().await;
+
+    if matches!(
+        engine.fail_after,
+        Some(fail_after) if engine.accepted_batches.load(Ordering::SeqCst) >= fail_after
+    ) {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    if let Some(idempotency_key) = idempotency_key {
+        if !state.processed_batches.insert(idempotency_key) {
+            return Ok(StatusCode::OK);
+        }
+    }
+
+    state.apply(data);
+    state
+        .write(&engine.state_path)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    engine.accepted_batches.fetch_add(1, Ordering::SeqCst);
+
+    Ok(StatusCode::OK)
+}
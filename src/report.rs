@@ -0,0 +1,23 @@
+use medici_data_sync::LoadedCourses;
+
+pub fn print_load_report(loaded_courses: &LoadedCourses) {
+    if !loaded_courses.warnings.is_empty() {
+        eprintln!("{}", loaded_courses.warnings);
+    }
+
+    for (path, merged_ids) in &loaded_courses.merged_ids {
+        for (removed_id, kept_id) in &merged_ids.questions {
+            eprintln!(
+                "{}: merged duplicate question {removed_id} into {kept_id}",
+                path.display()
+            );
+        }
+
+        for (removed_id, kept_id) in &merged_ids.question_options {
+            eprintln!(
+                "{}: merged duplicate question option {removed_id} into {kept_id}",
+                path.display()
+            );
+        }
+    }
+}
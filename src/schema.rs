@@ -0,0 +1,28 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+use medici_data_sync::{
+    course_data_schema, read_data_dir, read_dir_entry_data, write_data, RawCourseData,
+};
+
+pub fn schema(data_path: PathBuf, output_path: PathBuf) -> Result<()> {
+    let mut rules = vec![];
+
+    for dir_entry in read_data_dir(data_path)? {
+        let dir_entry = dir_entry?;
+        let path = dir_entry.path();
+        let raw_data = read_dir_entry_data(dir_entry)?;
+        let raw_course_data = RawCourseData::from_slice(&raw_data)
+            .with_context(|| format!("Could not parse course file {}", path.display()))?;
+
+        rules.extend(raw_course_data.rules);
+    }
+
+    let schema = course_data_schema(&rules);
+    write_data(output_path.clone(), serde_json::to_string_pretty(&schema)?)?;
+
+    println!("Schema written to {}", output_path.display());
+
+    Ok(())
+}
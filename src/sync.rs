@@ -1,39 +1,217 @@
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 
-use anyhow::{bail, Result};
-use secrecy::{ExposeSecret, Secret};
-use url::Url;
+use anyhow::{Context, Result};
+use clap::Args;
+use serde::{Deserialize, Serialize};
 
-use medici_data_sync::{load_courses_data_and_write_formatted, SyncData, SyncMetadata};
+use medici_data_sync::{
+    load_courses_data, load_courses_data_and_write_formatted, remove_synced_merged_ids, write_data,
+    CourseData, CourseEvaluationData, MergedIds, SyncBatch, SyncData, SyncMetadata,
+};
+
+use crate::engine::{Engine, EngineOptions};
+use crate::images::{delete_images, upload_images, S3Options};
+use crate::report::print_load_report;
+
+#[derive(Args, Clone, Debug)]
+pub struct BatchOptions {
+    /// Maximum number of entities sent in each sync-data request
+    #[clap(long, value_parser, value_name = "COUNT", default_value = "500")]
+    batch_size: usize,
+
+    #[clap(
+        long,
+        value_parser,
+        value_name = "PATH",
+        default_value = "./.sync-progress.json"
+    )]
+    progress_path: PathBuf,
+
+    /// Resume the last interrupted sync from its first unacknowledged batch
+    #[clap(long, value_parser, conflicts_with = "dry-run")]
+    resume: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct SyncProgress {
+    images_bucket_name: String,
+    batches: Vec<SyncBatch>,
+    acknowledged: usize,
+}
+
+impl SyncProgress {
+    fn load(path: &Path) -> Result<Self> {
+        let data = fs::read(path)
+            .with_context(|| format!("No sync progress found at {}", path.display()))?;
+
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    fn write(&self, path: &Path) -> Result<()> {
+        write_data(path.to_owned(), serde_json::to_string(self)?)
+    }
+}
 
 pub async fn sync(
     data_path: PathBuf,
     images_path: PathBuf,
-    engine_url: Url,
-    engine_key: Secret<String>,
+    engine_options: EngineOptions,
+    s3_options: S3Options,
+    batch_options: BatchOptions,
+    dry_run: bool,
+    json: bool,
 ) -> Result<()> {
-    let engine_client = engine_client(engine_key)?;
-    let mut sync_metadata = sync_metadata(&engine_client, engine_url.clone()).await?;
+    let engine = Engine::new(engine_options)?;
+
+    if batch_options.resume {
+        let progress = SyncProgress::load(&batch_options.progress_path)?;
+
+        return sync_batches(
+            &engine,
+            &s3_options,
+            progress,
+            &batch_options.progress_path,
+            data_path,
+        )
+        .await;
+    }
+
+    let mut sync_metadata = engine.sync_metadata().await?;
+    let known_ids = sync_metadata.known_ids();
+
+    let loaded_courses = if dry_run {
+        let mut loaded_courses = load_courses_data(data_path.clone(), &images_path, &known_ids)?;
+
+        for course_data in loaded_courses.courses_data.iter_mut() {
+            course_data.format();
+        }
+
+        loaded_courses
+    } else {
+        load_courses_data_and_write_formatted(data_path.clone(), images_path.clone(), &known_ids)
+            .await?
+    };
+
+    if !json {
+        print_load_report(&loaded_courses);
+    }
+
+    let SyncPlan {
+        data,
+        unchanged_images,
+    } = sync_plan(
+        &mut sync_metadata,
+        loaded_courses.courses_data,
+        &images_path,
+    )?;
+
+    if dry_run {
+        return print_sync_plan(&data, json);
+    }
+
+    let bucket = s3_options.bucket(&sync_metadata.images_bucket_name)?;
+    upload_images(&bucket, &data.images_to_sync, &unchanged_images).await?;
+
+    let progress = SyncProgress {
+        images_bucket_name: sync_metadata.images_bucket_name,
+        batches: data.into_batches(batch_options.batch_size),
+        acknowledged: 0,
+    };
+    progress.write(&batch_options.progress_path)?;
+
+    sync_batches(
+        &engine,
+        &s3_options,
+        progress,
+        &batch_options.progress_path,
+        data_path,
+    )
+    .await
+}
+
+async fn sync_batches(
+    engine: &Engine,
+    s3_options: &S3Options,
+    mut progress: SyncProgress,
+    progress_path: &Path,
+    data_path: PathBuf,
+) -> Result<()> {
+    let batch_count = progress.batches.len();
+
+    while progress.acknowledged < batch_count {
+        let batch = &progress.batches[progress.acknowledged];
+        let batch_number = progress.acknowledged + 1;
+
+        engine
+            .sync_data(&batch.data, batch.id)
+            .await
+            .with_context(|| {
+                format!("Batch {batch_number}/{batch_count} failed, run again with --resume")
+            })?;
+
+        println!(
+            "Synced batch {batch_number}/{batch_count}: {}",
+            batch.description
+        );
 
+        progress.acknowledged += 1;
+        progress.write(progress_path)?;
+    }
+
+    let mut merged_ids = MergedIds::default();
+
+    for batch in &progress.batches {
+        merged_ids.extend(&batch.data.merged_ids);
+    }
+
+    remove_synced_merged_ids(data_path, &merged_ids)?;
+
+    let images_to_delete: Vec<String> = progress
+        .batches
+        .iter()
+        .flat_map(|batch| batch.data.images_to_delete.iter().cloned())
+        .collect();
+
+    let bucket = s3_options.bucket(&progress.images_bucket_name)?;
+    delete_images(&bucket, &images_to_delete).await?;
+
+    fs::remove_file(progress_path)?;
+
+    Ok(())
+}
+
+struct SyncPlan {
+    data: SyncData,
+    unchanged_images: Vec<String>,
+}
+
+fn sync_plan(
+    sync_metadata: &mut SyncMetadata,
+    mut courses_data: Vec<CourseData>,
+    images_path: &Path,
+) -> Result<SyncPlan> {
     let mut courses_to_sync = vec![];
     let mut questions_to_sync = vec![];
     let mut question_options_to_sync = vec![];
     let mut course_evaluations_to_sync = vec![];
-
-    let mut courses_data =
-        load_courses_data_and_write_formatted(data_path, images_path.clone()).await?;
+    let mut images_to_sync = vec![];
+    let mut unchanged_images = vec![];
+    let mut merged_ids = MergedIds::default();
 
     for mut course_data in courses_data.drain(..) {
-        let skip_course = match sync_metadata.courses_metadata.remove(&course_data.key) {
-            Some(course_hash) if course_hash == course_data.hash => true,
-            _ => false,
-        };
+        merged_ids.extend(&course_data.merged_ids);
+
+        let skip_course = matches!(
+            sync_metadata.courses_metadata.remove(&course_data.key),
+            Some(course_hash) if course_hash == course_data.hash
+        );
 
         for course_evaluation_data in course_data.evaluations.drain(..) {
-            match sync_metadata
-                .course_evaluations_metadata
-                .remove(&course_evaluation_data.key)
-            {
+            let full_key =
+                CourseEvaluationData::full_key(&course_data.key, &course_evaluation_data.key);
+
+            match sync_metadata.course_evaluations_metadata.remove(&full_key) {
                 Some(question_option_hash)
                     if question_option_hash == course_evaluation_data.hash => {}
                 _ => course_evaluations_to_sync.push(course_evaluation_data),
@@ -41,11 +219,21 @@ pub async fn sync(
         }
 
         for mut question_data in course_data.questions.drain(..) {
-            let skip_question = match sync_metadata.questions_metadata.remove(&question_data.id) {
-                Some(question_hash) if question_hash == question_data.hash => true,
-                _ if !skip_course => false,
-                _ => true,
-            };
+            let skip_question = matches!(
+                sync_metadata.questions_metadata.remove(&question_data.id),
+                Some(question_hash) if question_hash == question_data.hash
+            );
+
+            if let Some(image_data) = question_data.image_data(images_path) {
+                let image_data = image_data?;
+
+                match sync_metadata.images_metadata.remove(&image_data.key) {
+                    Some(image_hash) if image_hash == image_data.hash => {
+                        unchanged_images.push(image_data.key)
+                    }
+                    _ => images_to_sync.push(image_data),
+                }
+            }
 
             for question_option_data in question_data.question_options.drain(..) {
                 match sync_metadata
@@ -82,70 +270,116 @@ pub async fn sync(
         .cloned()
         .collect();
 
-    sync_data(
-        &engine_client,
-        engine_url.clone(),
-        SyncData {
-            courses_to_sync,
-            courses_to_delete,
+    let images_to_delete = sync_metadata.images_metadata.keys().cloned().collect();
 
-            questions_to_sync,
-            questions_to_delete,
+    let data = SyncData {
+        courses_to_sync,
+        courses_to_delete,
 
-            question_options_to_sync,
-            question_options_to_delete,
+        questions_to_sync,
+        questions_to_delete,
 
-            course_evaluations_to_sync,
-            course_evaluations_to_delete,
-        },
-    )
-    .await?;
+        question_options_to_sync,
+        question_options_to_delete,
 
-    sync_images(images_path, &sync_metadata.images_bucket_name).await?;
+        course_evaluations_to_sync,
+        course_evaluations_to_delete,
 
-    Ok(())
-}
+        images_to_sync,
+        images_to_delete,
 
-fn engine_client(engine_key: Secret<String>) -> Result<reqwest::Client> {
-    let client = reqwest::Client::builder()
-        .default_headers(
-            [(
-                reqwest::header::AUTHORIZATION,
-                format!("Bearer {}", engine_key.expose_secret()).parse()?,
-            )]
-            .into_iter()
-            .collect(),
-        )
-        .build()?;
+        merged_ids,
+    };
 
-    Ok(client)
+    Ok(SyncPlan {
+        data,
+        unchanged_images,
+    })
 }
 
-async fn sync_metadata(client: &reqwest::Client, engine_url: Url) -> Result<SyncMetadata> {
-    let url = engine_url.join("sync-metadata")?;
+fn print_sync_plan(data: &SyncData, json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(data)?);
 
-    Ok(client.get(url).send().await?.json().await?)
-}
+        return Ok(());
+    }
 
-async fn sync_data(client: &reqwest::Client, engine_url: Url, data: SyncData) -> Result<()> {
-    let url = engine_url.join("sync-data")?;
-    let response = client.post(url).json(&data).send().await?;
+    print_plan_section(
+        "Courses",
+        data.courses_to_sync.iter().map(|course| course.key.clone()),
+        data.courses_to_delete.iter().cloned(),
+    );
+    print_plan_section(
+        "Course evaluations",
+        data.course_evaluations_to_sync
+            .iter()
+            .map(|evaluation| match &evaluation.course_key {
+                Some(course_key) => CourseEvaluationData::full_key(course_key, &evaluation.key),
+                None => evaluation.key.clone(),
+            }),
+        data.course_evaluations_to_delete.iter().cloned(),
+    );
+    print_plan_section(
+        "Questions",
+        data.questions_to_sync.iter().map(|question| {
+            format!(
+                "{} ({})",
+                question.id,
+                question.course_key.as_deref().unwrap_or_default()
+            )
+        }),
+        data.questions_to_delete.iter().map(ToString::to_string),
+    );
+    print_plan_section(
+        "Question options",
+        data.question_options_to_sync.iter().map(|question_option| {
+            match question_option.question_id {
+                Some(question_id) => format!("{} (question {question_id})", question_option.id),
+                None => question_option.id.to_string(),
+            }
+        }),
+        data.question_options_to_delete
+            .iter()
+            .map(ToString::to_string),
+    );
+    print_plan_section(
+        "Images",
+        data.images_to_sync.iter().map(|image| image.key.clone()),
+        data.images_to_delete.iter().cloned(),
+    );
 
-    if response.status().is_success() {
-        Ok(())
-    } else {
-        bail!("Error {}", response.status())
+    if !data.merged_ids.is_empty() {
+        println!("Merged ids:");
+
+        for (removed_id, kept_id) in data
+            .merged_ids
+            .questions
+            .iter()
+            .chain(&data.merged_ids.question_options)
+        {
+            println!("  {removed_id} -> {kept_id}");
+        }
     }
+
+    Ok(())
 }
 
-async fn sync_images(images_path: PathBuf, bucket_name: &str) -> Result<()> {
-    tokio::process::Command::new("aws")
-        .arg("s3")
-        .arg("sync")
-        .arg(images_path)
-        .arg(format!("s3://{bucket_name}"))
-        .status()
-        .await?;
+fn print_plan_section(
+    title: &str,
+    to_sync: impl ExactSizeIterator<Item = String>,
+    to_delete: impl ExactSizeIterator<Item = String>,
+) {
+    println!(
+        "{title}: {} to sync, {} to delete",
+        to_sync.len(),
+        to_delete.len()
+    );
 
-    Ok(())
+    for item in to_sync {
+        println!("  + {item}");
+    }
+
+    for item in to_delete {
+        println!("  - {item}");
+    }
 }
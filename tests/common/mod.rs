@@ -0,0 +1,174 @@
+#![allow(dead_code)]
+
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+const ENGINE_KEY: &str = "test-engine-key";
+
+pub struct MockEngine {
+    child: Child,
+    url: String,
+}
+
+impl MockEngine {
+    pub fn start(state_path: &Path, args: &[&str]) -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_data-sync"))
+            .arg("mock-engine")
+            .args(["--address", "127.0.0.1:0", "--engine-key", ENGINE_KEY])
+            .arg("--state-path")
+            .arg(state_path)
+            .args(args)
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("could not start the mock engine");
+
+        let mut line = String::new();
+        BufReader::new(child.stdout.take().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+
+        let url = line
+            .trim()
+            .strip_prefix("Mock engine listening on ")
+            .unwrap_or_else(|| panic!("unexpected mock engine output: {line}"))
+            .to_owned();
+
+        Self { child, url }
+    }
+}
+
+impl Drop for MockEngine {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+pub struct Workspace {
+    path: PathBuf,
+}
+
+impl Workspace {
+    pub fn new() -> Self {
+        let path = env::temp_dir().join(format!("data-sync-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(path.join("data")).unwrap();
+        fs::create_dir_all(path.join("images")).unwrap();
+
+        Self { path }
+    }
+
+    pub fn path(&self, relative_path: &str) -> PathBuf {
+        self.path.join(relative_path)
+    }
+
+    pub fn start_engine(&self, args: &[&str]) -> MockEngine {
+        MockEngine::start(&self.path("mock-engine.json"), args)
+    }
+
+    pub fn write_course(&self, course_key: &str, course: &Value) {
+        fs::write(
+            self.path(&format!("data/{course_key}.json")),
+            serde_json::to_string_pretty(course).unwrap(),
+        )
+        .unwrap();
+    }
+
+    pub fn read_course(&self, course_key: &str) -> Value {
+        read_json(&self.path(&format!("data/{course_key}.json")))
+    }
+
+    pub fn remove_course(&self, course_key: &str) {
+        fs::remove_file(self.path(&format!("data/{course_key}.json"))).unwrap();
+    }
+
+    pub fn engine_state(&self) -> Value {
+        read_json(&self.path("mock-engine.json"))
+    }
+
+    pub fn data_sync(&self, engine: &MockEngine, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_data-sync"))
+            .current_dir(&self.path)
+            .args(args)
+            .env("ENGINE_URL", &engine.url)
+            .env("ENGINE_KEY", ENGINE_KEY)
+            .env("AWS_ACCESS_KEY_ID", "test")
+            .env("AWS_SECRET_ACCESS_KEY", "test")
+            .output()
+            .unwrap()
+    }
+
+    pub fn sync(&self, engine: &MockEngine, args: &[&str]) {
+        let output = self.data_sync(engine, &[&["sync"], args].concat());
+
+        assert_success(&output);
+    }
+
+    pub fn sync_plan(&self, engine: &MockEngine) -> Value {
+        let output = self.data_sync(engine, &["sync", "--dry-run", "--json"]);
+        assert_success(&output);
+
+        serde_json::from_slice(&output.stdout).unwrap()
+    }
+}
+
+impl Drop for Workspace {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+pub fn course(name: &str, questions: Value) -> Value {
+    json!({
+        "name": name,
+        "short_name": name,
+        "aliases": [],
+        "year": 1,
+        "evaluations": [
+            { "key": "p1", "name": "Parcial 1" },
+            { "key": "p2", "name": "Parcial 2" },
+        ],
+        "questions": questions,
+    })
+}
+
+pub fn question(evaluation: &str, text: &str) -> Value {
+    json!({
+        "evaluation": evaluation,
+        "source": "partial",
+        "text": text,
+        "options": [
+            { "text": "Yes", "correct": true },
+            { "text": "No" },
+        ],
+    })
+}
+
+pub fn assert_success(output: &Output) {
+    assert!(
+        output.status.success(),
+        "data-sync failed\nstdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+pub fn assert_empty_plan(plan: &Value) {
+    for (field, value) in plan.as_object().unwrap() {
+        assert!(
+            value.as_array().map_or(true, Vec::is_empty),
+            "expected `{field}` to be empty, got {value}"
+        );
+    }
+
+    assert!(plan.get("merged_ids").is_none(), "expected no merged ids");
+}
+
+fn read_json(path: &Path) -> Value {
+    serde_json::from_slice(&fs::read(path).unwrap()).unwrap()
+}
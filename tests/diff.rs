@@ -0,0 +1,152 @@
+mod common;
+
+use serde_json::{json, Value};
+
+use common::{assert_success, course, question, MockEngine, Workspace};
+
+fn diff(workspace: &Workspace, engine: &MockEngine) -> Value {
+    let output = workspace.data_sync(engine, &["diff", "--json"]);
+    assert_success(&output);
+
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+fn ids(values: &Value) -> Vec<String> {
+    values
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|value| value["id"].as_str().unwrap().to_owned())
+        .collect()
+}
+
+fn sorted(mut ids: Vec<String>) -> Vec<String> {
+    ids.sort();
+
+    ids
+}
+
+#[test]
+fn diff_is_empty_after_sync() {
+    let workspace = Workspace::new();
+    let engine = workspace.start_engine(&[]);
+    workspace.write_course(
+        "a",
+        &course(
+            "Course A",
+            json!([
+                question("p1", "First question?"),
+                question("p2", "Second question?")
+            ]),
+        ),
+    );
+
+    workspace.sync(&engine, &[]);
+
+    assert_eq!(diff(&workspace, &engine), json!([]));
+}
+
+#[test]
+fn diff_reports_changed_fields_in_id_order() {
+    let workspace = Workspace::new();
+    let engine = workspace.start_engine(&[]);
+    workspace.write_course(
+        "a",
+        &course(
+            "Course A",
+            json!([
+                {
+                    "evaluation": "p1",
+                    "source": "partial",
+                    "text": "Question with many options?",
+                    "options": [
+                        { "text": "A", "correct": true },
+                        { "text": "B" },
+                        { "text": "C" },
+                        { "text": "D" },
+                        { "text": "E" },
+                    ],
+                },
+                question("p1", "Second question?"),
+                question("p2", "Third question?"),
+                question("p2", "Fourth question?"),
+            ]),
+        ),
+    );
+
+    workspace.sync(&engine, &[]);
+
+    let mut course_a = workspace.read_course("a");
+    let questions = course_a["questions"].as_array_mut().unwrap();
+    let changed_question = questions
+        .iter_mut()
+        .find(|question| question["options"].as_array().unwrap().len() == 5)
+        .unwrap();
+    let changed_question_id = changed_question["id"].as_str().unwrap().to_owned();
+    changed_question["text"] = json!("Question with fewer options?");
+    changed_question["options"]
+        .as_array_mut()
+        .unwrap()
+        .truncate(2);
+    questions.retain(|question| question["id"] == changed_question_id.as_str());
+    workspace.write_course("a", &course_a);
+
+    let diffs = diff(&workspace, &engine);
+    assert_eq!(diffs.as_array().unwrap().len(), 1);
+    assert_eq!(diffs[0]["course_key"], "a");
+
+    let question_diffs = &diffs[0]["questions"];
+    let question_ids = ids(question_diffs);
+    assert_eq!(question_ids.len(), 4);
+    assert_eq!(question_ids, sorted(question_ids.clone()));
+
+    let changed_question_diff = question_diffs
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|question_diff| question_diff["id"] == changed_question_id.as_str())
+        .unwrap();
+    assert_eq!(changed_question_diff["change"], "changed");
+    assert_eq!(
+        changed_question_diff["fields"],
+        json!([{
+            "field": "text",
+            "engine": "Question with many options?",
+            "local": "Question with fewer options?",
+        }])
+    );
+
+    let option_ids = ids(&changed_question_diff["options"]);
+    assert_eq!(option_ids.len(), 3);
+    assert_eq!(option_ids, sorted(option_ids.clone()));
+
+    for question_diff in question_diffs.as_array().unwrap() {
+        if question_diff["id"] != changed_question_id.as_str() {
+            assert_eq!(question_diff["change"], "removed");
+        }
+    }
+}
+
+#[test]
+fn diff_ignores_formatting_that_sync_applies() {
+    let workspace = Workspace::new();
+    let engine = workspace.start_engine(&[]);
+    workspace.write_course(
+        "a",
+        &course(
+            "Course A",
+            json!([
+                question("p1", "First question?"),
+                question("p2", "Second question?")
+            ]),
+        ),
+    );
+
+    workspace.sync(&engine, &[]);
+
+    let mut course_a = workspace.read_course("a");
+    course_a["questions"][0]["text"] = json!("  First question?  ");
+    workspace.write_course("a", &course_a);
+
+    assert_eq!(diff(&workspace, &engine), json!([]));
+}
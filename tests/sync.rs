@@ -0,0 +1,160 @@
+mod common;
+
+use serde_json::json;
+
+use common::{assert_empty_plan, course, question, Workspace};
+
+fn write_courses(workspace: &Workspace) {
+    workspace.write_course(
+        "a",
+        &course(
+            "Course A",
+            json!([
+                question("p1", "First question of A?"),
+                question("p2", "Second question of A?")
+            ]),
+        ),
+    );
+    workspace.write_course(
+        "b",
+        &course(
+            "Course B",
+            json!([
+                question("p1", "First question of B?"),
+                question("p2", "Second question of B?")
+            ]),
+        ),
+    );
+}
+
+fn question_id(workspace: &Workspace, course_key: &str, index: usize) -> String {
+    workspace.read_course(course_key)["questions"][index]["id"]
+        .as_str()
+        .unwrap()
+        .to_owned()
+}
+
+#[test]
+fn second_sync_has_nothing_to_sync() {
+    let workspace = Workspace::new();
+    let engine = workspace.start_engine(&[]);
+    write_courses(&workspace);
+
+    workspace.sync(&engine, &[]);
+
+    assert_empty_plan(&workspace.sync_plan(&engine));
+
+    let state = workspace.engine_state();
+    let evaluation_keys: Vec<&String> = state["course_evaluations"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .collect();
+    assert_eq!(evaluation_keys, ["a/p1", "a/p2", "b/p1", "b/p2"]);
+}
+
+#[test]
+fn sync_deletes_removed_data() {
+    let workspace = Workspace::new();
+    let engine = workspace.start_engine(&[]);
+    write_courses(&workspace);
+
+    workspace.sync(&engine, &[]);
+
+    let removed_question_ids = [
+        question_id(&workspace, "a", 1),
+        question_id(&workspace, "b", 0),
+        question_id(&workspace, "b", 1),
+    ];
+
+    let mut course_a = workspace.read_course("a");
+    course_a["questions"].as_array_mut().unwrap().truncate(1);
+    course_a["evaluations"].as_array_mut().unwrap().truncate(1);
+    workspace.write_course("a", &course_a);
+    workspace.remove_course("b");
+
+    let plan = workspace.sync_plan(&engine);
+    assert_eq!(plan["courses_to_delete"], json!(["b"]));
+
+    let mut evaluations_to_delete: Vec<&str> = plan["course_evaluations_to_delete"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|key| key.as_str().unwrap())
+        .collect();
+    evaluations_to_delete.sort_unstable();
+    assert_eq!(evaluations_to_delete, ["a/p2", "b/p1", "b/p2"]);
+
+    let mut questions_to_delete: Vec<&str> = plan["questions_to_delete"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|id| id.as_str().unwrap())
+        .collect();
+    questions_to_delete.sort_unstable();
+    let mut expected_question_ids: Vec<&str> =
+        removed_question_ids.iter().map(String::as_str).collect();
+    expected_question_ids.sort_unstable();
+    assert_eq!(questions_to_delete, expected_question_ids);
+    assert_eq!(
+        plan["question_options_to_delete"].as_array().unwrap().len(),
+        6
+    );
+
+    workspace.sync(&engine, &[]);
+
+    assert_empty_plan(&workspace.sync_plan(&engine));
+
+    let state = workspace.engine_state();
+    assert_eq!(state["courses"].as_object().unwrap().len(), 1);
+    assert_eq!(state["questions"].as_object().unwrap().len(), 1);
+    assert_eq!(state["question_options"].as_object().unwrap().len(), 2);
+}
+
+#[test]
+fn resume_after_failed_batch() {
+    let workspace = Workspace::new();
+    write_courses(&workspace);
+
+    let engine = workspace.start_engine(&["--fail-after", "2"]);
+    let output = workspace.data_sync(
+        &engine,
+        &["sync", "--batch-size", "1", "--max-retries", "0"],
+    );
+    drop(engine);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Batch 3/"));
+    assert!(workspace.path(".sync-progress.json").exists());
+
+    let engine = workspace.start_engine(&[]);
+    workspace.sync(&engine, &["--resume"]);
+
+    assert!(!workspace.path(".sync-progress.json").exists());
+    assert_empty_plan(&workspace.sync_plan(&engine));
+}
+
+#[test]
+fn fresh_sync_after_failed_batch() {
+    let workspace = Workspace::new();
+    write_courses(&workspace);
+
+    let engine = workspace.start_engine(&["--fail-after", "1"]);
+    let output = workspace.data_sync(
+        &engine,
+        &["sync", "--batch-size", "1", "--max-retries", "0"],
+    );
+    drop(engine);
+
+    assert!(!output.status.success());
+    std::fs::remove_file(workspace.path(".sync-progress.json")).unwrap();
+
+    let engine = workspace.start_engine(&[]);
+    workspace.sync(&engine, &[]);
+
+    assert_empty_plan(&workspace.sync_plan(&engine));
+
+    let state = workspace.engine_state();
+    assert_eq!(state["questions"].as_object().unwrap().len(), 4);
+    assert_eq!(state["question_options"].as_object().unwrap().len(), 8);
+}